@@ -4,12 +4,19 @@ use anchor_spl::{
     token_2022::spl_token_2022,
 };
 use anyhow::Result;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program, sysvar};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_instruction, system_program, sysvar,
+};
 
 use raydium_cp_swap::accounts as raydium_cp_accounts;
 use raydium_cp_swap::instruction as raydium_cp_instructions;
 use raydium_cp_swap::{
-    states::{AMM_CONFIG_SEED, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED, POOL_VAULT_SEED},
+    states::{
+        ObservationHistoryState, AMM_CONFIG_SEED, OBSERVATION_SEED, POOL_LP_MINT_SEED, POOL_SEED,
+        POOL_VAULT_SEED,
+    },
     AUTH_SEED,
 };
 use std::rc::Rc;
@@ -58,6 +65,8 @@ pub fn initialize_pool_instr(
     };
 
     let (authority, __bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
+    let (event_authority, __bump) =
+        Pubkey::find_program_address(&[b"__event_authority"], &program.id());
     let (token_0_vault, __bump) = Pubkey::find_program_address(
         &[
             POOL_VAULT_SEED.as_bytes(),
@@ -115,6 +124,8 @@ pub fn initialize_pool_instr(
             associated_token_program: spl_associated_token_account::id(),
             system_program: system_program::id(),
             rent: sysvar::rent::id(),
+            event_authority,
+            program: program.id(),
         })
         .args(raydium_cp_instructions::Initialize {
             init_amount_0,
@@ -137,6 +148,8 @@ pub fn initialize_pool_instr(
 pub fn deposit_instr(
     config: &ClientConfig,
     pool_id: Pubkey,
+    amm_config: Pubkey,
+    observation_state: Pubkey,
     token_0_mint: Pubkey,
     token_1_mint: Pubkey,
     token_lp_mint: Pubkey,
@@ -156,6 +169,8 @@ pub fn deposit_instr(
     let program = client.program(config.raydium_cp_program)?;
 
     let (authority, __bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
+    let (event_authority, __bump) =
+        Pubkey::find_program_address(&[b"__event_authority"], &program.id());
 
     let instructions = program
         .request()
@@ -173,6 +188,10 @@ pub fn deposit_instr(
             vault_0_mint: token_0_mint,
             vault_1_mint: token_1_mint,
             lp_mint: token_lp_mint,
+            amm_config,
+            observation_state,
+            event_authority,
+            program: program.id(),
         })
         .args(raydium_cp_instructions::Deposit {
             lp_token_amount,
@@ -186,6 +205,8 @@ pub fn deposit_instr(
 pub fn withdraw_instr(
     config: &ClientConfig,
     pool_id: Pubkey,
+    amm_config: Pubkey,
+    observation_state: Pubkey,
     token_0_mint: Pubkey,
     token_1_mint: Pubkey,
     token_lp_mint: Pubkey,
@@ -205,6 +226,8 @@ pub fn withdraw_instr(
     let program = client.program(config.raydium_cp_program)?;
 
     let (authority, __bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
+    let (event_authority, __bump) =
+        Pubkey::find_program_address(&[b"__event_authority"], &program.id());
 
     let instructions = program
         .request()
@@ -223,6 +246,10 @@ pub fn withdraw_instr(
             vault_1_mint: token_1_mint,
             lp_mint: token_lp_mint,
             memo_program: spl_memo::id(),
+            amm_config,
+            observation_state,
+            event_authority,
+            program: program.id(),
         })
         .args(raydium_cp_instructions::Withdraw {
             lp_token_amount,
@@ -238,6 +265,7 @@ pub fn swap_base_input_instr(
     pool_id: Pubkey,
     amm_config: Pubkey,
     observation_account: Pubkey,
+    observation_history: Option<Pubkey>,
     input_token_account: Pubkey,
     output_token_account: Pubkey,
     input_vault: Pubkey,
@@ -256,6 +284,8 @@ pub fn swap_base_input_instr(
     let program = client.program(config.raydium_cp_program)?;
 
     let (authority, __bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
+    let (event_authority, __bump) =
+        Pubkey::find_program_address(&[b"__event_authority"], &program.id());
 
     let instructions = program
         .request()
@@ -273,7 +303,15 @@ pub fn swap_base_input_instr(
             input_token_mint,
             output_token_mint,
             observation_state: observation_account,
+            event_authority,
+            program: program.id(),
         })
+        .accounts(
+            observation_history
+                .into_iter()
+                .map(|key| AccountMeta::new(key, false))
+                .collect::<Vec<_>>(),
+        )
         .args(raydium_cp_instructions::SwapBaseInput {
             amount_in,
             minimum_amount_out,
@@ -287,6 +325,7 @@ pub fn swap_base_output_instr(
     pool_id: Pubkey,
     amm_config: Pubkey,
     observation_account: Pubkey,
+    observation_history: Option<Pubkey>,
     input_token_account: Pubkey,
     output_token_account: Pubkey,
     input_vault: Pubkey,
@@ -305,6 +344,8 @@ pub fn swap_base_output_instr(
     let program = client.program(config.raydium_cp_program)?;
 
     let (authority, __bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
+    let (event_authority, __bump) =
+        Pubkey::find_program_address(&[b"__event_authority"], &program.id());
 
     let instructions = program
         .request()
@@ -322,7 +363,15 @@ pub fn swap_base_output_instr(
             input_token_mint,
             output_token_mint,
             observation_state: observation_account,
+            event_authority,
+            program: program.id(),
         })
+        .accounts(
+            observation_history
+                .into_iter()
+                .map(|key| AccountMeta::new(key, false))
+                .collect::<Vec<_>>(),
+        )
         .args(raydium_cp_instructions::SwapBaseOutput {
             max_amount_in,
             amount_out,
@@ -330,3 +379,39 @@ pub fn swap_base_output_instr(
         .instructions()?;
     Ok(instructions)
 }
+
+pub fn initialize_observation_history_instr(
+    config: &ClientConfig,
+    pool_id: Pubkey,
+    observation_state: Pubkey,
+    observation_history: Pubkey,
+    rent_lamports: u64,
+) -> Result<Vec<Instruction>> {
+    let payer = read_keypair_file(&config.payer_path)?;
+    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
+    // Client.
+    let client = Client::new(url, Rc::new(payer));
+    let program = client.program(config.raydium_cp_program)?;
+
+    // the history account is too large to be created by the program
+    let mut instructions = vec![system_instruction::create_account(
+        &program.payer(),
+        &observation_history,
+        rent_lamports,
+        ObservationHistoryState::LEN as u64,
+        &program.id(),
+    )];
+    instructions.extend(
+        program
+            .request()
+            .accounts(raydium_cp_accounts::InitializeObservationHistory {
+                payer: program.payer(),
+                pool_state: pool_id,
+                observation_state,
+                observation_history,
+            })
+            .args(raydium_cp_instructions::InitializeObservationHistory {})
+            .instructions()?,
+    );
+    Ok(instructions)
+}
@@ -130,25 +130,7 @@ pub fn handle_program_log(
                 }
             };
 
-        if borsh_bytes.len() < DISCRIMINATOR_LEN {
-            return Err(ClientError::LogParseError(
-                format!("log data is too short: {}", log).to_string(),
-            ));
-        }
-        let disc = &borsh_bytes[..DISCRIMINATOR_LEN];
-        let mut slice: &[u8] = &borsh_bytes[DISCRIMINATOR_LEN..];
-
-        match disc {
-            SwapEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
-            }
-            LpChangeEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<LpChangeEvent>(&mut slice)?);
-            }
-            _ => {
-                println!("unknow event: {}", l);
-            }
-        }
+        handle_program_event(&borsh_bytes)?;
         return Ok((None, false));
     } else {
         let (program, did_pop) = handle_system_log(self_program_str, l);
@@ -171,6 +153,123 @@ fn handle_system_log(this_program_str: &str, log: &str) -> (Option<String>, bool
     }
 }
 
+/// Decodes an event, emitted to the logs or by self-CPI
+pub fn handle_program_event(data: &[u8]) -> Result<(), ClientError> {
+    if data.len() < DISCRIMINATOR_LEN {
+        return Err(ClientError::LogParseError(
+            format!("event data is too short: {:?}", data).to_string(),
+        ));
+    }
+    let disc = &data[..DISCRIMINATOR_LEN];
+    let mut slice: &[u8] = &data[DISCRIMINATOR_LEN..];
+
+    match disc {
+        SwapEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SwapEvent>(&mut slice)?);
+        }
+        SwapEventV2::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SwapEventV2>(&mut slice)?);
+        }
+        LpChangeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<LpChangeEvent>(&mut slice)?);
+        }
+        ConfigUpdatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<ConfigUpdatedEvent>(&mut slice)?);
+        }
+        ConfigChangeScheduledEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<ConfigChangeScheduledEvent>(&mut slice)?
+            );
+        }
+        ConfigChangeAppliedEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<ConfigChangeAppliedEvent>(&mut slice)?
+            );
+        }
+        PriceBreakerTrippedEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<PriceBreakerTrippedEvent>(&mut slice)?
+            );
+        }
+        PoolCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolCreatedEvent>(&mut slice)?);
+        }
+        AmmConfigCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<AmmConfigCreatedEvent>(&mut slice)?);
+        }
+        PoolCreatorChangeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolCreatorChangeEvent>(&mut slice)?);
+        }
+        PoolConfigMigrationScheduledEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<PoolConfigMigrationScheduledEvent>(&mut slice)?
+            );
+        }
+        PoolConfigMigratedEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<PoolConfigMigratedEvent>(&mut slice)?
+            );
+        }
+        PoolCreatorSettingsUpdatedEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<PoolCreatorSettingsUpdatedEvent>(&mut slice)?
+            );
+        }
+        CreatorFeeSplitUpdatedEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<CreatorFeeSplitUpdatedEvent>(&mut slice)?
+            );
+        }
+        AmmConfigStatusUpdatedEvent::DISCRIMINATOR => {
+            println!(
+                "{:#?}",
+                decode_event::<AmmConfigStatusUpdatedEvent>(&mut slice)?
+            );
+        }
+        PoolClosedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolClosedEvent>(&mut slice)?);
+        }
+        PoolCancelledEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolCancelledEvent>(&mut slice)?);
+        }
+        ReservesSyncedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<ReservesSyncedEvent>(&mut slice)?);
+        }
+        PoolSkimmedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolSkimmedEvent>(&mut slice)?);
+        }
+        DonateEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<DonateEvent>(&mut slice)?);
+        }
+        PoolPausedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolPausedEvent>(&mut slice)?);
+        }
+        PoolStatusUpdatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PoolStatusUpdatedEvent>(&mut slice)?);
+        }
+        CollectFeeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<CollectFeeEvent>(&mut slice)?);
+        }
+        PermissionChangeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PermissionChangeEvent>(&mut slice)?);
+        }
+        AdminChangeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<AdminChangeEvent>(&mut slice)?);
+        }
+        _ => {
+            println!("unknow event: {:?}", disc);
+        }
+    }
+    Ok(())
+}
+
 fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
     slice: &mut &[u8],
 ) -> Result<T, ClientError> {
@@ -307,6 +406,10 @@ pub fn handle_program_instruction(
             format!("instruction data is too short: {}", instr_data).to_string(),
         ));
     }
+    // events emitted by self-CPI are prefixed with the event instruction tag
+    if let Some(event_data) = data.strip_prefix(anchor_lang::event::EVENT_IX_TAG_LE) {
+        return handle_program_event(event_data);
+    }
     let disc = &data[..DISCRIMINATOR_LEN];
     let mut ix_data: &[u8] = &data[DISCRIMINATOR_LEN..];
 
@@ -338,14 +441,12 @@ pub fn handle_program_instruction(
             let ix = decode_instruction::<instruction::UpdateAmmConfig>(&mut ix_data).unwrap();
             #[derive(Debug)]
             pub struct UpdateAmmConfig {
-                pub param: u8,
-                pub value: u64,
+                pub update: AmmConfigUpdate,
             }
             impl From<instruction::UpdateAmmConfig> for UpdateAmmConfig {
                 fn from(instr: instruction::UpdateAmmConfig) -> UpdateAmmConfig {
                     UpdateAmmConfig {
-                        param: instr.param,
-                        value: instr.value,
+                        update: instr.update,
                     }
                 }
             }
@@ -385,6 +486,41 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", UpdatePoolStatus::from(ix));
         }
+        instruction::UpdateAmmConfigStatus::DISCRIMINATOR => {
+            let ix =
+                decode_instruction::<instruction::UpdateAmmConfigStatus>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct UpdateAmmConfigStatus {
+                pub status: u8,
+            }
+            impl From<instruction::UpdateAmmConfigStatus> for UpdateAmmConfigStatus {
+                fn from(instr: instruction::UpdateAmmConfigStatus) -> UpdateAmmConfigStatus {
+                    UpdateAmmConfigStatus {
+                        status: instr.status,
+                    }
+                }
+            }
+            println!("{:#?}", UpdateAmmConfigStatus::from(ix));
+        }
+        instruction::PausePool::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::PausePool>(&mut ix_data).unwrap();
+            #[derive(Debug)]
+            pub struct PausePool {
+                pub status: u8,
+                pub pause_until: u64,
+                pub reason: u8,
+            }
+            impl From<instruction::PausePool> for PausePool {
+                fn from(instr: instruction::PausePool) -> PausePool {
+                    PausePool {
+                        status: instr.status,
+                        pause_until: instr.pause_until,
+                        reason: instr.reason,
+                    }
+                }
+            }
+            println!("{:#?}", PausePool::from(ix));
+        }
         instruction::CollectProtocolFee::DISCRIMINATOR => {
             let ix = decode_instruction::<instruction::CollectProtocolFee>(&mut ix_data).unwrap();
             #[derive(Debug)]
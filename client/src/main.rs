@@ -120,6 +120,9 @@ pub enum RaydiumCpCommands {
         user_input_token: Pubkey,
         amount_out_less_fee: u64,
     },
+    InitializeObservationHistory {
+        pool_id: Pubkey,
+    },
     DecodeInstruction {
         instr_hex_data: String,
     },
@@ -291,6 +294,8 @@ fn main() -> Result<()> {
             let deposit_instr = deposit_instr(
                 &pool_config,
                 pool_id,
+                pool_state.amm_config,
+                pool_state.observation_key,
                 pool_state.token_0_mint,
                 pool_state.token_1_mint,
                 pool_state.lp_mint,
@@ -405,6 +410,8 @@ fn main() -> Result<()> {
             let withdraw_instr = withdraw_instr(
                 &pool_config,
                 pool_id,
+                pool_state.amm_config,
+                pool_state.observation_key,
                 pool_state.token_0_mint,
                 pool_state.token_1_mint,
                 pool_state.lp_mint,
@@ -536,7 +543,7 @@ fn main() -> Result<()> {
                 u128::from(total_input_token_amount),
                 u128::from(total_output_token_amount),
                 amm_config_state.trade_fee_rate,
-                pool_state.adjust_creator_fee_rate(amm_config_state.creator_fee_rate),
+                pool_state.adjust_creator_fee_rate(&amm_config_state),
                 amm_config_state.protocol_fee_rate,
                 amm_config_state.fund_fee_rate,
                 pool_state.is_creator_fee_on_input(trade_direction).unwrap(),
@@ -570,6 +577,11 @@ fn main() -> Result<()> {
                 pool_id,
                 pool_state.amm_config,
                 pool_state.observation_key,
+                if pool_state.observation_history_key == Pubkey::default() {
+                    None
+                } else {
+                    Some(pool_state.observation_history_key)
+                },
                 user_input_token,
                 user_output_token,
                 input_vault,
@@ -693,7 +705,7 @@ fn main() -> Result<()> {
                 u128::from(total_input_token_amount),
                 u128::from(total_output_token_amount),
                 amm_config_state.trade_fee_rate,
-                pool_state.adjust_creator_fee_rate(amm_config_state.creator_fee_rate),
+                pool_state.adjust_creator_fee_rate(&amm_config_state),
                 amm_config_state.protocol_fee_rate,
                 amm_config_state.fund_fee_rate,
                 pool_state.is_creator_fee_on_input(trade_direction).unwrap(),
@@ -730,6 +742,11 @@ fn main() -> Result<()> {
                 pool_id,
                 pool_state.amm_config,
                 pool_state.observation_key,
+                if pool_state.observation_history_key == Pubkey::default() {
+                    None
+                } else {
+                    Some(pool_state.observation_history_key)
+                },
                 user_input_token,
                 user_output_token,
                 input_vault,
@@ -753,6 +770,34 @@ fn main() -> Result<()> {
             let signature = send_txn(&rpc_client, &txn, true)?;
             println!("{}", signature);
         }
+        RaydiumCpCommands::InitializeObservationHistory { pool_id } => {
+            let pool_state: raydium_cp_swap::states::PoolState = program.account(pool_id)?;
+            let observation_history_keypair = Keypair::new();
+            println!(
+                "observation_history:{}",
+                observation_history_keypair.pubkey()
+            );
+            let rent_lamports = rpc_client.get_minimum_balance_for_rent_exemption(
+                raydium_cp_swap::states::ObservationHistoryState::LEN,
+            )?;
+            let initialize_observation_history_instr = initialize_observation_history_instr(
+                &pool_config,
+                pool_id,
+                pool_state.observation_key,
+                observation_history_keypair.pubkey(),
+                rent_lamports,
+            )?;
+            let signers = vec![&payer, &observation_history_keypair];
+            let recent_hash = rpc_client.get_latest_blockhash()?;
+            let txn = Transaction::new_signed_with_payer(
+                &initialize_observation_history_instr,
+                Some(&payer.pubkey()),
+                &signers,
+                recent_hash,
+            );
+            let signature = send_txn(&rpc_client, &txn, true)?;
+            println!("{}", signature);
+        }
         RaydiumCpCommands::DecodeInstruction { instr_hex_data } => {
             handle_program_instruction(&instr_hex_data, InstructionDecodeType::BaseHex)?;
         }
@@ -75,6 +75,20 @@ pub struct SwapResult {
     pub creator_fee: u128,
 }
 
+impl SwapResult {
+    /// The vault amounts after the swap excluding the protocol, fund and creator fees,
+    /// unlike the new vault amounts these include the trade fee kept by LPs
+    pub fn reserves_after(&self) -> Option<(u128, u128)> {
+        Some((
+            self.new_input_vault_amount
+                .checked_add(self.trade_fee)?
+                .checked_sub(self.protocol_fee)?
+                .checked_sub(self.fund_fee)?,
+            self.new_output_vault_amount,
+        ))
+    }
+}
+
 /// Concrete struct to wrap around the trait object which performs calculation.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct CurveCalculator {}
@@ -40,4 +40,20 @@ pub enum ErrorCode {
     InvalidFeeModel,
     #[msg("Fee is zero")]
     NoFeeCollect,
+    #[msg("Not enough oracle observations")]
+    InsufficientObservation,
+    #[msg("Spot price deviates too much from the TWAP")]
+    PriceDeviationExceeded,
+    #[msg("Price moved too much in the current window")]
+    PriceMoveLimitExceeded,
+    #[msg("Invalid fee rate")]
+    InvalidFeeRate,
+    #[msg("Fee rate raises must be scheduled")]
+    ConfigChangeTimelocked,
+    #[msg("No config change is ready to be applied")]
+    ConfigChangeNotReady,
+    #[msg("The permission doesn't allow it")]
+    PermissionDenied,
+    #[msg("Pool still has liquidity or uncollected fees")]
+    PoolNotEmpty,
 }
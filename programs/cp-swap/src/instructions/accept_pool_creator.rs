@@ -0,0 +1,29 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptPoolCreator<'info> {
+    /// The proposed pool creator
+    #[account(address = pool_state.load()?.pending_pool_creator)]
+    pub pending_creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn accept_pool_creator(ctx: Context<AcceptPoolCreator>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let old_creator = pool_state.accept_pool_creator(ctx.accounts.pending_creator.key())?;
+
+    emit_event!(
+        ctx,
+        PoolCreatorChangeEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            old_creator,
+            new_creator: ctx.accounts.pending_creator.key(),
+            pending: false,
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed admin
+    #[account(address = global_admin.pending_admin @ ErrorCode::InvalidOwner)]
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let new_admin = ctx.accounts.pending_admin.key();
+    let global_admin = &mut ctx.accounts.global_admin;
+    let old_admin = global_admin.admin;
+    global_admin.admin = new_admin;
+    global_admin.pending_admin = Pubkey::default();
+
+    emit_event!(
+        ctx,
+        AdminChangeEvent {
+            old_account: old_admin,
+            new_account: new_admin,
+            role: AdminRole::Admin as u8,
+        }
+    );
+    Ok(())
+}
@@ -2,11 +2,12 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClosePermissionPda<'info> {
     #[account(
         mut,
-        address = crate::admin::ID @ ErrorCode::InvalidOwner
+        address = global_admin.admin @ ErrorCode::InvalidOwner
     )]
     pub owner: Signer<'info>,
 
@@ -26,8 +27,25 @@ pub struct ClosePermissionPda<'info> {
     pub permission: Account<'info, Permission>,
 
     pub system_program: Program<'info, System>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
-pub fn close_permission_pda(_ctx: Context<ClosePermissionPda>) -> Result<()> {
+pub fn close_permission_pda(ctx: Context<ClosePermissionPda>) -> Result<()> {
+    emit_event!(
+        ctx,
+        PermissionChangeEvent {
+            permission: ctx.accounts.permission.key(),
+            permission_authority: ctx.accounts.permission_authority.key(),
+            change_type: 1,
+        }
+    );
     Ok(())
 }
@@ -6,10 +6,11 @@ use anchor_spl::token::Token;
 use anchor_spl::token_interface::Mint;
 use anchor_spl::token_interface::Token2022;
 use anchor_spl::token_interface::TokenAccount;
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CollectFundFee<'info> {
     /// Only admin or fund_owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.fund_owner || owner.key() == crate::admin::ID) @ ErrorCode::InvalidOwner)]
+    #[account(constraint = (owner.key() == amm_config.fund_owner || owner.key() == global_admin.admin) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -68,6 +69,15 @@ pub struct CollectFundFee<'info> {
 
     /// The SPL program 2022 to perform token transfers
     pub token_program_2022: Program<'info, Token2022>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
 pub fn collect_fund_fee(
@@ -118,5 +128,17 @@ pub fn collect_fund_fee(
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
     )?;
 
+    emit_event!(
+        ctx,
+        CollectFeeEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            recipient_token_0_account: ctx.accounts.recipient_token_0_account.key(),
+            recipient_token_1_account: ctx.accounts.recipient_token_1_account.key(),
+            amount_0,
+            amount_1,
+            fee_type: 1,
+        }
+    );
+
     Ok(())
 }
@@ -7,10 +7,11 @@ use anchor_spl::token_interface::Mint;
 use anchor_spl::token_interface::Token2022;
 use anchor_spl::token_interface::TokenAccount;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CollectProtocolFee<'info> {
     /// Only admin or owner can collect fee now
-    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == crate::admin::ID) @ ErrorCode::InvalidOwner)]
+    #[account(constraint = (owner.key() == amm_config.protocol_owner || owner.key() == global_admin.admin) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
     /// CHECK: pool vault and lp mint authority
@@ -69,6 +70,15 @@ pub struct CollectProtocolFee<'info> {
 
     /// The SPL program 2022 to perform token transfers
     pub token_program_2022: Program<'info, Token2022>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
 pub fn collect_protocol_fee(
@@ -127,5 +137,17 @@ pub fn collect_protocol_fee(
         &[&[crate::AUTH_SEED.as_bytes(), &[auth_bump]]],
     )?;
 
+    emit_event!(
+        ctx,
+        CollectFeeEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            recipient_token_0_account: ctx.accounts.recipient_token_0_account.key(),
+            recipient_token_1_account: ctx.accounts.recipient_token_1_account.key(),
+            amount_0,
+            amount_1,
+            fee_type: 0,
+        }
+    );
+
     Ok(())
 }
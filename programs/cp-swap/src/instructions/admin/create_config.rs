@@ -3,13 +3,14 @@ use crate::states::*;
 use anchor_lang::prelude::*;
 use std::ops::DerefMut;
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(index: u16)]
 pub struct CreateAmmConfig<'info> {
     /// Address to be set as protocol owner.
     #[account(
         mut,
-        address = crate::admin::ID @ ErrorCode::InvalidOwner
+        address = global_admin.admin @ ErrorCode::InvalidOwner
     )]
     pub owner: Signer<'info>,
 
@@ -27,6 +28,15 @@ pub struct CreateAmmConfig<'info> {
     pub amm_config: Account<'info, AmmConfig>,
 
     pub system_program: Program<'info, System>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
 pub fn create_amm_config(
@@ -49,5 +59,21 @@ pub fn create_amm_config(
     amm_config.create_pool_fee = create_pool_fee;
     amm_config.fund_owner = ctx.accounts.owner.key();
     amm_config.creator_fee_rate = creator_fee_rate;
+    amm_config.validate()?;
+
+    emit_event!(
+        ctx,
+        AmmConfigCreatedEvent {
+            amm_config: ctx.accounts.amm_config.key(),
+            index,
+            trade_fee_rate,
+            protocol_fee_rate,
+            fund_fee_rate,
+            create_pool_fee,
+            creator_fee_rate,
+            protocol_owner: ctx.accounts.owner.key(),
+            fund_owner: ctx.accounts.owner.key(),
+        }
+    );
     Ok(())
 }
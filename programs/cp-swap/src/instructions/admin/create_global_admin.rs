@@ -0,0 +1,48 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateGlobalAdmin<'info> {
+    /// The bootstrap admin, becomes the first admin
+    #[account(
+        mut,
+        address = crate::admin::ID @ ErrorCode::InvalidOwner
+    )]
+    pub owner: Signer<'info>,
+
+    /// Initialize global admin account to store the admin address
+    #[account(
+        init,
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump,
+        payer = owner,
+        space = GlobalAdmin::LEN
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_global_admin(ctx: Context<CreateGlobalAdmin>) -> Result<()> {
+    let global_admin = ctx.accounts.global_admin.deref_mut();
+    global_admin.bump = ctx.bumps.global_admin;
+    global_admin.admin = ctx.accounts.owner.key();
+    global_admin.pending_admin = Pubkey::default();
+    global_admin.pause_guardian = ctx.accounts.owner.key();
+    global_admin.fee_manager = ctx.accounts.owner.key();
+
+    emit_event!(
+        ctx,
+        AdminChangeEvent {
+            old_account: Pubkey::default(),
+            new_account: ctx.accounts.owner.key(),
+            role: AdminRole::Admin as u8,
+        }
+    );
+    Ok(())
+}
@@ -3,11 +3,12 @@ use crate::states::*;
 use anchor_lang::prelude::*;
 use std::ops::DerefMut;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreatePermissionPda<'info> {
     #[account(
         mut,
-        address = crate::admin::ID @ ErrorCode::InvalidOwner
+        address = global_admin.admin @ ErrorCode::InvalidOwner
     )]
     pub owner: Signer<'info>,
 
@@ -28,10 +29,48 @@ pub struct CreatePermissionPda<'info> {
     pub permission: Account<'info, Permission>,
 
     pub system_program: Program<'info, System>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
-pub fn create_permission_pda(ctx: Context<CreatePermissionPda>) -> Result<()> {
+pub fn create_permission_pda(
+    ctx: Context<CreatePermissionPda>,
+    allowed_amm_configs: Vec<Pubkey>,
+    expire_time: u64,
+    max_pool_count: u64,
+    max_creator_fee_rate: u64,
+    allowed_creator_fee_on: u8,
+) -> Result<()> {
+    require_gte!(
+        PERMISSION_AMM_CONFIG_NUM,
+        allowed_amm_configs.len(),
+        ErrorCode::InvalidInput
+    );
+    require_gt!(8, allowed_creator_fee_on, ErrorCode::InvalidInput);
     let permission = ctx.accounts.permission.deref_mut();
     permission.authority = ctx.accounts.permission_authority.key();
+    permission.allowed_amm_configs[..allowed_amm_configs.len()]
+        .copy_from_slice(&allowed_amm_configs);
+    permission.expire_time = expire_time;
+    permission.max_pool_count = max_pool_count;
+    permission.pool_count = 0;
+    permission.max_creator_fee_rate = max_creator_fee_rate;
+    permission.allowed_creator_fee_on = allowed_creator_fee_on;
+
+    emit_event!(
+        ctx,
+        PermissionChangeEvent {
+            permission: ctx.accounts.permission.key(),
+            permission_authority: ctx.accounts.permission_authority.key(),
+            change_type: 0,
+        }
+    );
     Ok(())
 }
@@ -7,6 +7,12 @@ pub use update_config::*;
 pub mod update_pool_status;
 pub use update_pool_status::*;
 
+pub mod pause_pool;
+pub use pause_pool::*;
+
+pub mod update_amm_config_status;
+pub use update_amm_config_status::*;
+
 pub mod collect_protocol_fee;
 pub use collect_protocol_fee::*;
 
@@ -18,3 +24,24 @@ pub use create_permission_pda::*;
 
 pub mod close_permission_pda;
 pub use close_permission_pda::*;
+
+pub mod create_global_admin;
+pub use create_global_admin::*;
+
+pub mod propose_admin;
+pub use propose_admin::*;
+
+pub mod accept_admin;
+pub use accept_admin::*;
+
+pub mod schedule_config_change;
+pub use schedule_config_change::*;
+
+pub mod schedule_pool_config_migration;
+pub use schedule_pool_config_migration::*;
+
+pub mod update_admin_role;
+pub use update_admin_role::*;
+
+pub mod skim;
+pub use skim::*;
@@ -0,0 +1,44 @@
+use super::update_pool_status::UpdatePoolStatus;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+pub fn pause_pool(
+    ctx: Context<UpdatePoolStatus>,
+    status: u8,
+    pause_until: u64,
+    reason: u8,
+) -> Result<()> {
+    require!(status != 0 && status < 8, ErrorCode::InvalidInput);
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    require_gt!(pause_until, block_timestamp, ErrorCode::InvalidInput);
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    if ctx.accounts.authority.key() != ctx.accounts.global_admin.admin {
+        // the pause guardian can't shorten a pause in effect
+        let disabled = pool_state.status_at(block_timestamp);
+        let status_pause_until = pool_state.status_pause_until;
+        for (index, current_pause_until) in status_pause_until.into_iter().enumerate() {
+            let s = 1u8 << index;
+            if status & s != 0 && disabled & s != 0 {
+                require!(
+                    current_pause_until != 0 && pause_until >= current_pause_until,
+                    ErrorCode::InvalidOwner
+                );
+            }
+        }
+    }
+    pool_state.pause_until(status, pause_until);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    emit_event!(
+        ctx,
+        PoolPausedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            authority: ctx.accounts.authority.key(),
+            status,
+            pause_until,
+            reason,
+        }
+    );
+    Ok(())
+}
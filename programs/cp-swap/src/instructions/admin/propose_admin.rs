@@ -0,0 +1,34 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    /// The current admin
+    #[account(address = global_admin.admin @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
+}
+
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    // proposing the default address cancels a pending transfer
+    emit_event!(
+        ctx,
+        AdminChangeEvent {
+            old_account: ctx.accounts.global_admin.pending_admin,
+            new_account: new_admin,
+            role: AdminRole::PendingAdmin as u8,
+        }
+    );
+    ctx.accounts.global_admin.pending_admin = new_admin;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use super::update_config::UpdateAmmConfig;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+pub fn schedule_config_change(
+    ctx: Context<UpdateAmmConfig>,
+    update: AmmConfigUpdate,
+    effective_time: u64,
+) -> Result<()> {
+    ctx.accounts.check_role(&update)?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let (param, value) =
+        ctx.accounts
+            .amm_config
+            .schedule_change(update, effective_time, block_timestamp)?;
+
+    emit_event!(
+        ctx,
+        ConfigChangeScheduledEvent {
+            amm_config: ctx.accounts.amm_config.key(),
+            param,
+            value,
+            effective_time,
+        }
+    );
+    Ok(())
+}
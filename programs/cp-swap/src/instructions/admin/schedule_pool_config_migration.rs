@@ -0,0 +1,66 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SchedulePoolConfigMigration<'info> {
+    /// Only the fee manager raises the fees of a pool
+    #[account(
+        mut,
+        constraint = global_admin.is_fee_manager(&fee_manager.key()) @ ErrorCode::InvalidOwner
+    )]
+    pub fee_manager: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the pool is moved to
+    #[account(constraint = new_amm_config.key() != pool_state.load()?.amm_config @ ErrorCode::InvalidInput)]
+    pub new_amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The scheduled migration of the pool, replaced if one is already scheduled
+    #[account(
+        init_if_needed,
+        seeds = [
+            POOL_CONFIG_MIGRATION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = fee_manager,
+        space = PoolConfigMigration::LEN
+    )]
+    pub pool_config_migration: Account<'info, PoolConfigMigration>,
+
+    /// Global admin account stores the fee manager address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Box<Account<'info, GlobalAdmin>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn schedule_pool_config_migration(
+    ctx: Context<SchedulePoolConfigMigration>,
+    effective_time: u64,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let new_amm_config = ctx.accounts.new_amm_config.key();
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let pool_config_migration = &mut ctx.accounts.pool_config_migration;
+    pool_config_migration.bump = ctx.bumps.pool_config_migration;
+    pool_config_migration.schedule(pool_id, new_amm_config, effective_time, block_timestamp)?;
+
+    emit_event!(
+        ctx,
+        PoolConfigMigrationScheduledEvent {
+            pool_id,
+            new_amm_config,
+            effective_time,
+        }
+    );
+    Ok(())
+}
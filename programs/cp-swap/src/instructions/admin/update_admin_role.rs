@@ -0,0 +1,41 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateAdminRole<'info> {
+    /// Only the admin assigns the roles
+    #[account(address = global_admin.admin @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
+}
+
+pub fn update_admin_role(ctx: Context<UpdateAdminRole>, role: u8, account: Pubkey) -> Result<()> {
+    let global_admin = &mut ctx.accounts.global_admin;
+    let role = AdminRole::from_assignable(role).ok_or(ErrorCode::InvalidInput)?;
+    let role_account = match role {
+        AdminRole::PauseGuardian => &mut global_admin.pause_guardian,
+        AdminRole::FeeManager => &mut global_admin.fee_manager,
+        AdminRole::Admin | AdminRole::PendingAdmin => return err!(ErrorCode::InvalidInput),
+    };
+    let old_account = std::mem::replace(role_account, account);
+
+    emit_event!(
+        ctx,
+        AdminChangeEvent {
+            old_account,
+            new_account: account,
+            role: role as u8,
+        }
+    );
+    Ok(())
+}
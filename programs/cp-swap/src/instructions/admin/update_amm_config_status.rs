@@ -0,0 +1,48 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateAmmConfigStatus<'info> {
+    /// The admin, or the pause guardian which can only disable
+    #[account(
+        constraint = global_admin.can_pause(&authority.key()) @ ErrorCode::InvalidOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// Amm config account to be changed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
+}
+
+pub fn update_amm_config_status(ctx: Context<UpdateAmmConfigStatus>, status: u8) -> Result<()> {
+    require_gte!(7, status, ErrorCode::InvalidInput);
+    let amm_config = &mut ctx.accounts.amm_config;
+    if ctx.accounts.authority.key() != ctx.accounts.global_admin.admin {
+        // a set bit disables, the pause guardian can't clear any
+        require_eq!(status | amm_config.status, status, ErrorCode::InvalidOwner);
+    }
+    let old_status = amm_config.status;
+    amm_config.status = status;
+
+    emit_event!(
+        ctx,
+        AmmConfigStatusUpdatedEvent {
+            amm_config: ctx.accounts.amm_config.key(),
+            authority: ctx.accounts.authority.key(),
+            old_status,
+            new_status: status,
+        }
+    );
+    Ok(())
+}
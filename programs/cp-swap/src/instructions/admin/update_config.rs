@@ -1,85 +1,67 @@
-use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
 use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdateAmmConfig<'info> {
-    /// The amm config owner or admin
-    #[account(address = crate::admin::ID @ ErrorCode::InvalidOwner)]
+    /// The admin, or the fee manager for the fee rates
+    #[account(
+        constraint = (owner.key() == global_admin.admin || global_admin.is_fee_manager(&owner.key())) @ ErrorCode::InvalidOwner
+    )]
     pub owner: Signer<'info>,
 
     /// Amm config account to be changed
     #[account(mut)]
     pub amm_config: Account<'info, AmmConfig>,
-}
-
-pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
-    let amm_config = &mut ctx.accounts.amm_config;
-    let match_param = Some(param);
-    match match_param {
-        Some(0) => update_trade_fee_rate(amm_config, value),
-        Some(1) => update_protocol_fee_rate(amm_config, value),
-        Some(2) => update_fund_fee_rate(amm_config, value),
-        Some(3) => {
-            let new_procotol_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_protocol_owner(amm_config, new_procotol_owner)?;
-        }
-        Some(4) => {
-            let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
-            set_new_fund_owner(amm_config, new_fund_owner)?;
-        }
-        Some(5) => amm_config.create_pool_fee = value,
-        Some(6) => amm_config.disable_create_pool = if value == 0 { false } else { true },
-        Some(7) => update_creator_fee_rate(amm_config, value),
-        _ => return err!(ErrorCode::InvalidInput),
-    }
-
-    Ok(())
-}
-
-fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_rate: u64) {
-    assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(protocol_fee_rate + amm_config.fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    amm_config.protocol_fee_rate = protocol_fee_rate;
-}
-
-fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u64) {
-    assert!(trade_fee_rate + amm_config.creator_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
-    amm_config.trade_fee_rate = trade_fee_rate;
-}
 
-fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u64) {
-    assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    assert!(fund_fee_rate + amm_config.protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-    amm_config.fund_fee_rate = fund_fee_rate;
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
-fn update_creator_fee_rate(amm_config: &mut Account<AmmConfig>, creator_fee_rate: u64) {
-    assert!(creator_fee_rate + amm_config.trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
-    amm_config.creator_fee_rate = creator_fee_rate;
-}
-
-fn set_new_protocol_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) -> Result<()> {
-    require_keys_neq!(new_owner, Pubkey::default());
+pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, update: AmmConfigUpdate) -> Result<()> {
+    ctx.accounts.check_role(&update)?;
+    let amm_config = &mut ctx.accounts.amm_config;
+    // raises have to wait for the timelock of `schedule_config_change`
+    require!(
+        !update.is_timelocked_raise(amm_config),
+        ErrorCode::ConfigChangeTimelocked
+    );
+    let old_value = amm_config.apply_update(update)?;
     #[cfg(feature = "enable-log")]
     msg!(
-        "amm_config, old_protocol_owner:{}, new_owner:{}",
-        amm_config.protocol_owner.to_string(),
-        new_owner.key().to_string()
+        "amm_config, old_value:{:?}, new_value:{:?}",
+        old_value,
+        update
     );
-    amm_config.protocol_owner = new_owner;
-    Ok(())
-}
 
-fn set_new_fund_owner(amm_config: &mut Account<AmmConfig>, new_fund_owner: Pubkey) -> Result<()> {
-    require_keys_neq!(new_fund_owner, Pubkey::default());
-    #[cfg(feature = "enable-log")]
-    msg!(
-        "amm_config, old_fund_owner:{}, new_fund_owner:{}",
-        amm_config.fund_owner.to_string(),
-        new_fund_owner.key().to_string()
+    emit_event!(
+        ctx,
+        ConfigUpdatedEvent {
+            amm_config: ctx.accounts.amm_config.key(),
+            old_value,
+            new_value: update,
+        }
     );
-    amm_config.fund_owner = new_fund_owner;
     Ok(())
 }
+
+impl<'info> UpdateAmmConfig<'info> {
+    /// The fee rates are changed by the fee manager, everything else by the admin
+    pub(crate) fn check_role(&self, update: &AmmConfigUpdate) -> Result<()> {
+        let owner = self.owner.key();
+        let allowed = if update.is_fee_rate() {
+            self.global_admin.is_fee_manager(&owner)
+        } else {
+            owner == self.global_admin.admin
+        };
+        require!(allowed, ErrorCode::InvalidOwner);
+        Ok(())
+    }
+}
@@ -1,20 +1,46 @@
+use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdatePoolStatus<'info> {
+    /// The admin, or the pause guardian which can only disable
     #[account(
-        address = crate::admin::ID
+        constraint = global_admin.can_pause(&authority.key()) @ ErrorCode::InvalidOwner
     )]
     pub authority: Signer<'info>,
 
     #[account(mut)]
     pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Account<'info, GlobalAdmin>,
 }
 
 pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
     require_gte!(255, status);
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let disabled = pool_state.status_at(Clock::get()?.unix_timestamp as u64);
+    if ctx.accounts.authority.key() != ctx.accounts.global_admin.admin {
+        // a set bit disables, the pause guardian can't clear any
+        require_eq!(status | disabled, status, ErrorCode::InvalidOwner);
+    }
+    emit_event!(
+        ctx,
+        PoolStatusUpdatedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            authority: ctx.accounts.authority.key(),
+            old_status: disabled,
+            new_status: status,
+        }
+    );
     pool_state.set_status(status);
     pool_state.recent_epoch = Clock::get()?.epoch;
     Ok(())
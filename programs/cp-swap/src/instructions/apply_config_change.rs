@@ -0,0 +1,32 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ApplyConfigChange<'info> {
+    /// Amm config account with a scheduled change
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+pub fn apply_config_change(ctx: Context<ApplyConfigChange>) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let param = amm_config.pending_change_param;
+    let new_value = amm_config.pending_change_value;
+    let old_value = amm_config.apply_scheduled_change(block_timestamp)?;
+
+    emit_event!(
+        ctx,
+        ConfigChangeAppliedEvent {
+            amm_config: ctx.accounts.amm_config.key(),
+            param,
+            old_value: old_value
+                .fee_rate_param()
+                .map(|(_, value)| value)
+                .unwrap_or_default(),
+            new_value,
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+use anchor_spl::token_interface::Token2022;
+use anchor_spl::token_interface::TokenAccount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelPool<'info> {
+    /// Only pool creator can cancel the pool, receives the liquidity and the rent
+    #[account(mut, address = pool_state.load()?.pool_creator)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// The pool to cancel
+    #[account(mut, close = creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Pool lp mint
+    #[account(
+        mut,
+        address = pool_state.load()?.lp_mint
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The creator's lp token account, must hold the whole lp supply, closed
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = creator,
+    )]
+    pub creator_lp_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The creator's token account to receive token_0
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        token::authority = creator,
+    )]
+    pub creator_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The creator's token account to receive token_1
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        token::authority = creator,
+    )]
+    pub creator_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the oracle observations of the pool, closed with it
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    /// The SPL program to burn lp tokens and perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+pub fn cancel_pool<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CancelPool<'info>>,
+) -> Result<()> {
+    let pool_accounts = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        pool_state.check_cancellable(
+            Clock::get()?.unix_timestamp as u64,
+            ctx.accounts.creator_lp_token.amount,
+            ctx.accounts.lp_mint.supply,
+        )?;
+        PoolAccountsToClose {
+            vault_authority: ctx.accounts.authority.to_account_info(),
+            auth_bump: pool_state.auth_bump,
+            token_0_vault: &ctx.accounts.token_0_vault,
+            token_1_vault: &ctx.accounts.token_1_vault,
+            vault_0_mint: &ctx.accounts.vault_0_mint,
+            vault_1_mint: &ctx.accounts.vault_1_mint,
+            token_program: &ctx.accounts.token_program,
+            token_program_2022: &ctx.accounts.token_program_2022,
+            observation_state: ctx.accounts.observation_state.to_account_info(),
+            observation_history_key: pool_state.observation_history_key,
+            creator_fee_split_key: pool_state.creator_fee_split,
+        }
+    };
+
+    let lp_amount = ctx.accounts.creator_lp_token.amount;
+    token_burn(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.lp_mint.to_account_info(),
+        ctx.accounts.creator_lp_token.to_account_info(),
+        lp_amount,
+        &[],
+    )?;
+    close_token_account(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.creator_lp_token.to_account_info(),
+        ctx.accounts.creator.to_account_info(),
+        &[],
+    )?;
+
+    // The creator gets back everything, including the share of the locked lp amount
+    let (amount_0, amount_1) = pool_accounts.close(
+        ctx.accounts.creator_token_0.to_account_info(),
+        ctx.accounts.creator_token_1.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(
+        ctx,
+        PoolCancelledEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            pool_creator: ctx.accounts.creator.key(),
+            lp_amount,
+            amount_0,
+            amount_1,
+        }
+    );
+
+    Ok(())
+}
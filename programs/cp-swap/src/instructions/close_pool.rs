@@ -0,0 +1,140 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+use anchor_spl::token_interface::Token2022;
+use anchor_spl::token_interface::TokenAccount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    /// Only admin or pool creator can close the pool
+    #[account(constraint = (authority.key() == global_admin.admin || authority.key() == pool_state.load()?.pool_creator) @ ErrorCode::InvalidOwner)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: receives the rent of the closed accounts
+    #[account(mut, address = pool_state.load()?.pool_creator)]
+    pub pool_creator: UncheckedAccount<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// The pool to close
+    #[account(mut, close = pool_creator)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Amm config account stores protocol_owner
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The protocol owner's token_0 account, receives the dust left in the vault
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        constraint = protocol_token_0_account.owner == amm_config.protocol_owner @ ErrorCode::InvalidOwner
+    )]
+    pub protocol_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The protocol owner's token_1 account, receives the dust left in the vault
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        constraint = protocol_token_1_account.owner == amm_config.protocol_owner @ ErrorCode::InvalidOwner
+    )]
+    pub protocol_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the oracle observations of the pool, closed with it
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Box<Account<'info, GlobalAdmin>>,
+}
+
+pub fn close_pool<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClosePool<'info>>,
+) -> Result<()> {
+    let pool_accounts = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        pool_state.check_closable()?;
+        PoolAccountsToClose {
+            vault_authority: ctx.accounts.vault_authority.to_account_info(),
+            auth_bump: pool_state.auth_bump,
+            token_0_vault: &ctx.accounts.token_0_vault,
+            token_1_vault: &ctx.accounts.token_1_vault,
+            vault_0_mint: &ctx.accounts.vault_0_mint,
+            vault_1_mint: &ctx.accounts.vault_1_mint,
+            token_program: &ctx.accounts.token_program,
+            token_program_2022: &ctx.accounts.token_program_2022,
+            observation_state: ctx.accounts.observation_state.to_account_info(),
+            observation_history_key: pool_state.observation_history_key,
+            creator_fee_split_key: pool_state.creator_fee_split,
+        }
+    };
+    // The locked lp amount still backs some dust, it goes to the protocol
+    let (amount_0, amount_1) = pool_accounts.close(
+        ctx.accounts.protocol_token_0_account.to_account_info(),
+        ctx.accounts.protocol_token_1_account.to_account_info(),
+        &ctx.accounts.pool_creator.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    emit_event!(
+        ctx,
+        PoolClosedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            authority: ctx.accounts.authority.key(),
+            pool_creator: ctx.accounts.pool_creator.key(),
+            amount_0,
+            amount_1,
+        }
+    );
+
+    Ok(())
+}
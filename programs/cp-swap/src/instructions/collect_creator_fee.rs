@@ -6,6 +6,7 @@ use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::Mint;
 use anchor_spl::token_interface::TokenAccount;
 use anchor_spl::token_interface::TokenInterface;
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CollectCreatorFee<'info> {
     /// Only pool creator can collect fee
@@ -87,6 +88,12 @@ pub struct CollectCreatorFee<'info> {
 
 pub fn collect_creator_fee(ctx: Context<CollectCreatorFee>) -> Result<()> {
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // while split, the creator fees are only paid out by `distribute_creator_fee`
+    require_keys_eq!(
+        pool_state.creator_fee_split,
+        Pubkey::default(),
+        ErrorCode::NotApproved
+    );
     let creator_fees_token_0 = pool_state.creator_fees_token_0;
     let creator_fees_token_1 = pool_state.creator_fees_token_1;
     if creator_fees_token_0 == 0 && creator_fees_token_1 == 0 {
@@ -121,5 +128,16 @@ pub fn collect_creator_fee(ctx: Context<CollectCreatorFee>) -> Result<()> {
     pool_state.creator_fees_token_1 = 0;
     pool_state.recent_epoch = Clock::get()?.epoch;
 
+    emit_event!(
+        ctx,
+        CollectFeeEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            recipient_token_0_account: ctx.accounts.creator_token_0.key(),
+            recipient_token_1_account: ctx.accounts.creator_token_1.key(),
+            amount_0: creator_fees_token_0,
+            amount_1: creator_fees_token_1,
+            fee_type: 2,
+        }
+    );
     Ok(())
 }
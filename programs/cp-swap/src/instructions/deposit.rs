@@ -7,6 +7,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     /// Pays to mint the position
@@ -82,6 +83,14 @@ pub struct Deposit<'info> {
         address = pool_state.load()?.lp_mint @ ErrorCode::IncorrectLpMint)
     ]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Amm config account stores the config-wide status and the price deviation guard
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: the oracle of the pool, ObservationState or ObservationStateV2
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
 }
 
 pub fn deposit(
@@ -93,9 +102,34 @@ pub fn deposit(
     require_gt!(lp_token_amount, 0);
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit) {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, oracle::block_timestamp()) {
         return err!(ErrorCode::NotApproved);
     }
+    if !ctx
+        .accounts
+        .amm_config
+        .get_status_by_bit(PoolStatusBitIndex::Deposit)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    // only the pool creator can deposit before the pool opens, so that it can still be cancelled
+    if oracle::block_timestamp() < pool_state.open_time
+        && ctx.accounts.owner.key() != pool_state.pool_creator
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    let (token_0_price_x64, token_1_price_x64) = pool_state.token_price_x64(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    PoolObservation::try_from(&ctx.accounts.observation_state.to_account_info())?
+        .check_price_deviation(
+            oracle::block_timestamp(),
+            ctx.accounts.amm_config.price_deviation_window(),
+            token_0_price_x64,
+            token_1_price_x64,
+            ctx.accounts.amm_config.max_price_deviation_rate,
+        )?;
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
         ctx.accounts.token_0_vault.amount,
         ctx.accounts.token_1_vault.amount,
@@ -143,17 +177,20 @@ pub fn deposit(
         transfer_token_1_fee
     );
 
-    emit!(LpChangeEvent {
-        pool_id,
-        lp_amount_before: pool_state.lp_supply,
-        token_0_vault_before: total_token_0_amount,
-        token_1_vault_before: total_token_1_amount,
-        token_0_amount,
-        token_1_amount,
-        token_0_transfer_fee: transfer_token_0_fee,
-        token_1_transfer_fee: transfer_token_1_fee,
-        change_type: 0
-    });
+    emit_event!(
+        ctx,
+        LpChangeEvent {
+            pool_id,
+            lp_amount_before: pool_state.lp_supply,
+            token_0_vault_before: total_token_0_amount,
+            token_1_vault_before: total_token_1_amount,
+            token_0_amount,
+            token_1_amount,
+            token_0_transfer_fee: transfer_token_0_fee,
+            token_1_transfer_fee: transfer_token_1_fee,
+            change_type: 0
+        }
+    );
 
     if transfer_token_0_amount > maximum_token_0_amount
         || transfer_token_1_amount > maximum_token_1_amount
@@ -190,6 +227,10 @@ pub fn deposit(
     )?;
 
     pool_state.lp_supply = pool_state.lp_supply.checked_add(lp_token_amount).unwrap();
+    pool_state.set_reserves(
+        total_token_0_amount.checked_add(token_0_amount).unwrap(),
+        total_token_1_amount.checked_add(token_1_amount).unwrap(),
+    );
 
     token_mint_to(
         ctx.accounts.authority.to_account_info(),
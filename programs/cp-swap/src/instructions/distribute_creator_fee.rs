@@ -0,0 +1,132 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DistributeCreatorFee<'info> {
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// Pool state stores accumulated creator fee amount
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The creator fee split of the pool
+    #[account(address = pool_state.load()?.creator_fee_split)]
+    pub creator_fee_split: Account<'info, CreatorFeeSplit>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Spl token program or token program 2022
+    pub token_0_program: Interface<'info, TokenInterface>,
+    /// Spl token program or token program 2022
+    pub token_1_program: Interface<'info, TokenInterface>,
+}
+
+/// Distributes the creator fees to the recipients of the split, anyone can call it.
+/// The remaining accounts are the token_0 and token_1 accounts of each recipient, in order.
+pub fn distribute_creator_fee<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DistributeCreatorFee<'info>>,
+) -> Result<()> {
+    let creator_fee_split = &ctx.accounts.creator_fee_split;
+    let recipient_count = creator_fee_split.recipient_count as usize;
+    require_eq!(
+        ctx.remaining_accounts.len(),
+        recipient_count * 2,
+        ErrorCode::InvalidInput
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let creator_fees_token_0 = pool_state.creator_fees_token_0;
+    let creator_fees_token_1 = pool_state.creator_fees_token_1;
+    if creator_fees_token_0 == 0 && creator_fees_token_1 == 0 {
+        return err!(ErrorCode::NoFeeCollect);
+    }
+    let amounts_0 = creator_fee_split.split(creator_fees_token_0);
+    let amounts_1 = creator_fee_split.split(creator_fees_token_1);
+
+    let signer_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]];
+    for (i, recipient_accounts) in ctx.remaining_accounts.chunks(2).enumerate() {
+        let recipient = creator_fee_split.recipients[i];
+        let recipient_token_0 = InterfaceAccount::<TokenAccount>::try_from(&recipient_accounts[0])?;
+        let recipient_token_1 = InterfaceAccount::<TokenAccount>::try_from(&recipient_accounts[1])?;
+        require!(
+            recipient_token_0.owner == recipient
+                && recipient_token_0.mint == ctx.accounts.vault_0_mint.key()
+                && recipient_token_1.owner == recipient
+                && recipient_token_1.mint == ctx.accounts.vault_1_mint.key(),
+            ErrorCode::InvalidInput
+        );
+
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_0_vault.to_account_info(),
+            recipient_accounts[0].clone(),
+            ctx.accounts.vault_0_mint.to_account_info(),
+            ctx.accounts.token_0_program.to_account_info(),
+            amounts_0[i],
+            ctx.accounts.vault_0_mint.decimals,
+            &[signer_seeds],
+        )?;
+
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_1_vault.to_account_info(),
+            recipient_accounts[1].clone(),
+            ctx.accounts.vault_1_mint.to_account_info(),
+            ctx.accounts.token_1_program.to_account_info(),
+            amounts_1[i],
+            ctx.accounts.vault_1_mint.decimals,
+            &[signer_seeds],
+        )?;
+
+        emit_event!(
+            ctx,
+            CollectFeeEvent {
+                pool_id: ctx.accounts.pool_state.key(),
+                recipient_token_0_account: recipient_accounts[0].key(),
+                recipient_token_1_account: recipient_accounts[1].key(),
+                amount_0: amounts_0[i],
+                amount_1: amounts_1[i],
+                fee_type: 2,
+            }
+        );
+    }
+
+    pool_state.creator_fees_token_0 = 0;
+    pool_state.creator_fees_token_1 = 0;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+    Ok(())
+}
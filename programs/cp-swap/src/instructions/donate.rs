@@ -0,0 +1,213 @@
+use crate::error::ErrorCode;
+use crate::instructions::{check_price_breaker, update_observation_before_reserves_change};
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Donate<'info> {
+    /// Pays the donated tokens
+    pub donor: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The donor's token account for token_0
+    #[account(
+        mut,
+        token::mint = token_0_vault.mint,
+        token::authority = donor
+    )]
+    pub token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The donor's token account for token_1
+    #[account(
+        mut,
+        token::mint = token_1_vault.mint,
+        token::authority = donor
+    )]
+    pub token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// token Program
+    pub token_program: Program<'info, Token>,
+
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Amm config account stores the status
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: the oracle of the pool, ObservationState or ObservationStateV2
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
+}
+
+pub fn donate<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Donate<'info>>,
+    token_0_amount: u64,
+    token_1_amount: u64,
+    proportional: bool,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, oracle::block_timestamp())
+        || !ctx
+            .accounts
+            .amm_config
+            .get_status_by_bit(PoolStatusBitIndex::Deposit)
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    // only the pool creator can donate before the pool opens, like it is for deposits
+    if oracle::block_timestamp() < pool_state.open_time
+        && ctx.accounts.donor.key() != pool_state.pool_creator
+    {
+        return err!(ErrorCode::NotApproved);
+    }
+    let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    // The amounts are received by the vaults, proportional ones keep the price unchanged
+    let (token_0_amount, token_1_amount) = if proportional {
+        pool_state.proportional_amounts(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+            token_0_amount,
+            token_1_amount,
+        )?
+    } else {
+        (token_0_amount, token_1_amount)
+    };
+    if token_0_amount == 0 && token_1_amount == 0 {
+        return err!(ErrorCode::ZeroTradingTokens);
+    }
+
+    let (transfer_token_0_amount, transfer_token_0_fee) = {
+        let transfer_fee =
+            get_transfer_inverse_fee(&ctx.accounts.vault_0_mint.to_account_info(), token_0_amount)?;
+        (
+            token_0_amount.checked_add(transfer_fee).unwrap(),
+            transfer_fee,
+        )
+    };
+    let (transfer_token_1_amount, transfer_token_1_fee) = {
+        let transfer_fee =
+            get_transfer_inverse_fee(&ctx.accounts.vault_1_mint.to_account_info(), token_1_amount)?;
+        (
+            token_1_amount.checked_add(transfer_fee).unwrap(),
+            transfer_fee,
+        )
+    };
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.donor.to_account_info(),
+        ctx.accounts.token_0_account.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.vault_0_mint.to_account_info(),
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        transfer_token_0_amount,
+        ctx.accounts.vault_0_mint.decimals,
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.donor.to_account_info(),
+        ctx.accounts.token_1_account.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.vault_1_mint.to_account_info(),
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        },
+        transfer_token_1_amount,
+        ctx.accounts.vault_1_mint.decimals,
+    )?;
+
+    update_observation_before_reserves_change(
+        pool_state,
+        &ctx.accounts.observation_state.to_account_info(),
+        ctx.remaining_accounts,
+        total_token_0_amount,
+        total_token_1_amount,
+    )?;
+    let (reserve_0, reserve_1) = (
+        total_token_0_amount.checked_add(token_0_amount).unwrap(),
+        total_token_1_amount.checked_add(token_1_amount).unwrap(),
+    );
+    // arbitrary amounts move the price like a swap does
+    let price_breaker_event = if proportional {
+        None
+    } else {
+        let (trade_direction, token_0_price_x64, price_x64) = reserves_change_price_x64(
+            total_token_0_amount,
+            total_token_1_amount,
+            reserve_0,
+            reserve_1,
+        )?;
+        check_price_breaker(
+            pool_id,
+            pool_state,
+            &ctx.accounts.amm_config,
+            oracle::block_timestamp(),
+            trade_direction,
+            token_0_price_x64,
+            price_x64,
+        )?
+    };
+    pool_state.set_reserves(reserve_0, reserve_1);
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    if let Some(event) = price_breaker_event {
+        emit_event!(ctx, event);
+    }
+    emit_event!(
+        ctx,
+        DonateEvent {
+            pool_id,
+            donor: ctx.accounts.donor.key(),
+            token_0_amount,
+            token_1_amount,
+            token_0_transfer_fee: transfer_token_0_fee,
+            token_1_transfer_fee: transfer_token_1_fee,
+            reserve_0: pool_state.reserve_0,
+            reserve_1: pool_state.reserve_1,
+        }
+    );
+
+    Ok(())
+}
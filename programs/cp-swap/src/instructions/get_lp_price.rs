@@ -0,0 +1,62 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct GetLpPrice<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the oracle of the pool, ObservationState or ObservationStateV2
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
+}
+
+/// The value of one LP token unit, returned by `get_lp_price`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LpPrice {
+    /// The value in token_0, Q64.64
+    pub lp_price_token_0_x64: u128,
+    /// The value in token_1, Q64.64
+    pub lp_price_token_1_x64: u128,
+}
+
+pub fn get_lp_price(ctx: Context<GetLpPrice>, window: u64) -> Result<LpPrice> {
+    // a zero window would average over nothing and fall back to the manipulable spot price
+    require_gt!(window, 0, ErrorCode::InvalidInput);
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (token_0_price_x64, token_1_price_x64) = pool_state.token_price_x64(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    let (twap_token_0_price_x64, twap_token_1_price_x64) =
+        PoolObservation::try_from(&ctx.accounts.observation_state.to_account_info())?
+            .twap_price_x64(
+                oracle::block_timestamp(),
+                window,
+                token_0_price_x64,
+                token_1_price_x64,
+            )?;
+    let (lp_price_token_0_x64, lp_price_token_1_x64) = pool_state.fair_lp_price_x64(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+        twap_token_0_price_x64,
+        twap_token_1_price_x64,
+    )?;
+    Ok(LpPrice {
+        lp_price_token_0_x64,
+        lp_price_token_1_x64,
+    })
+}
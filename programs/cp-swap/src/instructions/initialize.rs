@@ -17,6 +17,7 @@ use anchor_spl::{
 };
 use std::ops::Deref;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     /// Address paying to create the pool. Can be anyone
@@ -62,9 +63,9 @@ pub struct Initialize<'info> {
     )]
     pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// pool lp mint
+    /// pool lp mint, the one of a closed or cancelled pool at the same address is reused
     #[account(
-        init,
+        init_if_needed,
         seeds = [
             POOL_LP_MINT_SEED.as_bytes(),
             pool_state.key().as_ref(),
@@ -74,6 +75,7 @@ pub struct Initialize<'info> {
         mint::authority = authority,
         payer = creator,
         mint::token_program = token_program,
+        constraint = lp_mint.supply == 0 @ ErrorCode::NotApproved,
     )]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -95,7 +97,7 @@ pub struct Initialize<'info> {
 
     /// creator lp token account
     #[account(
-        init,
+        init_if_needed,
         associated_token::mint = lp_mint,
         associated_token::authority = creator,
         payer = creator,
@@ -143,9 +145,9 @@ pub struct Initialize<'info> {
         ],
         bump,
         payer = creator,
-        space = ObservationState::LEN
+        space = ObservationStateV2::LEN
     )]
-    pub observation_state: AccountLoader<'info, ObservationState>,
+    pub observation_state: AccountLoader<'info, ObservationStateV2>,
 
     /// Program to create mint account and mint tokens
     pub token_program: Program<'info, Token>,
@@ -270,7 +272,7 @@ pub fn initialize(
         .unwrap()
         .integer_sqrt()
         .as_u64();
-    let lock_lp_amount = 100;
+    let (lock_lp_amount, user_lp_amount) = ctx.accounts.amm_config.initial_lp_amounts(liquidity)?;
     msg!(
         "liquidity:{}, lock_lp_amount:{}, vault_0_amount:{},vault_1_amount:{}",
         liquidity,
@@ -283,9 +285,7 @@ pub fn initialize(
         ctx.accounts.token_program.to_account_info(),
         ctx.accounts.lp_mint.to_account_info(),
         ctx.accounts.creator_lp_token.to_account_info(),
-        liquidity
-            .checked_sub(lock_lp_amount)
-            .ok_or(ErrorCode::InitLpAmountTooLess)?,
+        user_lp_amount,
         &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
     )?;
 
@@ -318,6 +318,7 @@ pub fn initialize(
     pool_state.initialize(
         ctx.bumps.authority,
         liquidity,
+        lock_lp_amount,
         open_time,
         ctx.accounts.creator.key(),
         ctx.accounts.amm_config.key(),
@@ -331,6 +332,31 @@ pub fn initialize(
         CreatorFeeOn::BothToken,
         false,
     );
+    pool_state.set_reserves(token_0_vault.amount, token_1_vault.amount);
+
+    emit_event!(
+        ctx,
+        PoolCreatedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            pool_creator: pool_state.pool_creator,
+            amm_config: pool_state.amm_config,
+            token_0_mint: pool_state.token_0_mint,
+            token_1_mint: pool_state.token_1_mint,
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            lp_mint: pool_state.lp_mint,
+            observation_key: pool_state.observation_key,
+            init_amount_0,
+            init_amount_1,
+            lp_amount: liquidity,
+            lock_lp_amount,
+            open_time,
+            creator_fee_on: pool_state.creator_fee_on,
+            enable_creator_fee: pool_state.enable_creator_fee,
+            creator_fee_rate: pool_state.adjust_creator_fee_rate(&ctx.accounts.amm_config),
+            with_permission: false,
+        }
+    );
 
     Ok(())
 }
@@ -0,0 +1,36 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeObservationHistory<'info> {
+    /// Anyone can create the history account of a pool
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The history is fed from the Q64.64 accumulators, pools with a legacy oracle are not supported
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationStateV2>,
+
+    /// Allocated by the client with `ObservationHistoryState::LEN` bytes in the same transaction
+    #[account(zero)]
+    pub observation_history: AccountLoader<'info, ObservationHistoryState>,
+}
+
+pub fn initialize_observation_history(ctx: Context<InitializeObservationHistory>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require_keys_eq!(
+        pool_state.observation_history_key,
+        Pubkey::default(),
+        ErrorCode::InvalidInput
+    );
+    pool_state.observation_history_key = ctx.accounts.observation_history.key();
+
+    let mut observation_history = ctx.accounts.observation_history.load_init()?;
+    observation_history.pool_id = pool_id;
+    observation_history.update(&*ctx.accounts.observation_state.load()?);
+    Ok(())
+}
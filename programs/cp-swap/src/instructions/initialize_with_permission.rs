@@ -17,6 +17,7 @@ use anchor_spl::{
 };
 use std::ops::Deref;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeWithPermission<'info> {
     /// Address paying to create the pool. Can be anyone
@@ -65,9 +66,9 @@ pub struct InitializeWithPermission<'info> {
     )]
     pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// pool lp mint
+    /// pool lp mint, the one of a closed or cancelled pool at the same address is reused
     #[account(
-        init,
+        init_if_needed,
         seeds = [
             POOL_LP_MINT_SEED.as_bytes(),
             pool_state.key().as_ref(),
@@ -77,6 +78,7 @@ pub struct InitializeWithPermission<'info> {
         mint::authority = authority,
         payer = payer,
         mint::token_program = token_program,
+        constraint = lp_mint.supply == 0 @ ErrorCode::NotApproved,
     )]
     pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -98,7 +100,7 @@ pub struct InitializeWithPermission<'info> {
 
     /// payer lp token account
     #[account(
-        init,
+        init_if_needed,
         associated_token::mint = lp_mint,
         associated_token::authority = payer,
         payer = payer,
@@ -146,12 +148,13 @@ pub struct InitializeWithPermission<'info> {
         ],
         bump,
         payer = payer,
-        space = ObservationState::LEN
+        space = ObservationStateV2::LEN
     )]
-    pub observation_state: AccountLoader<'info, ObservationState>,
+    pub observation_state: AccountLoader<'info, ObservationStateV2>,
 
     /// CHECK: PDA account used for permission verification.
     #[account(
+        mut,
         seeds = [
             PERMISSION_SEED.as_bytes(),
             payer.key().as_ref(),
@@ -178,6 +181,7 @@ pub fn initialize_with_permission(
     init_amount_1: u64,
     open_time: u64,
     creator_fee_on: CreatorFeeOn,
+    creator_fee_rate: u64,
 ) -> Result<()> {
     if !(is_supported_mint(&ctx.accounts.token_0_mint).unwrap()
         && is_supported_mint(&ctx.accounts.token_1_mint).unwrap())
@@ -190,6 +194,16 @@ pub fn initialize_with_permission(
     }
     let mut open_time = open_time;
     let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
+    let pool_creator_fee_rate = ctx
+        .accounts
+        .amm_config
+        .pool_creator_fee_rate(creator_fee_rate)?;
+    ctx.accounts.permission.use_for_pool(
+        ctx.accounts.amm_config.key(),
+        &creator_fee_on,
+        pool_creator_fee_rate,
+        block_timestamp,
+    )?;
     if open_time <= block_timestamp {
         open_time = block_timestamp + 1;
     }
@@ -283,7 +297,7 @@ pub fn initialize_with_permission(
         .unwrap()
         .integer_sqrt()
         .as_u64();
-    let lock_lp_amount = 100;
+    let (lock_lp_amount, user_lp_amount) = ctx.accounts.amm_config.initial_lp_amounts(liquidity)?;
     msg!(
         "liquidity:{}, lock_lp_amount:{}, vault_0_amount:{},vault_1_amount:{}",
         liquidity,
@@ -296,9 +310,7 @@ pub fn initialize_with_permission(
         ctx.accounts.token_program.to_account_info(),
         ctx.accounts.lp_mint.to_account_info(),
         ctx.accounts.payer_lp_token.to_account_info(),
-        liquidity
-            .checked_sub(lock_lp_amount)
-            .ok_or(ErrorCode::InitLpAmountTooLess)?,
+        user_lp_amount,
         &[&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]],
     )?;
 
@@ -331,6 +343,7 @@ pub fn initialize_with_permission(
     pool_state.initialize(
         ctx.bumps.authority,
         liquidity,
+        lock_lp_amount,
         open_time,
         ctx.accounts.creator.key(),
         ctx.accounts.amm_config.key(),
@@ -344,6 +357,33 @@ pub fn initialize_with_permission(
         creator_fee_on,
         true,
     );
+    pool_state.set_reserves(token_0_vault.amount, token_1_vault.amount);
+    pool_state.creator_fee_rate = creator_fee_rate;
+    pool_state.with_permission = true;
+
+    emit_event!(
+        ctx,
+        PoolCreatedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            pool_creator: pool_state.pool_creator,
+            amm_config: pool_state.amm_config,
+            token_0_mint: pool_state.token_0_mint,
+            token_1_mint: pool_state.token_1_mint,
+            token_0_vault: pool_state.token_0_vault,
+            token_1_vault: pool_state.token_1_vault,
+            lp_mint: pool_state.lp_mint,
+            observation_key: pool_state.observation_key,
+            init_amount_0,
+            init_amount_1,
+            lp_amount: liquidity,
+            lock_lp_amount,
+            open_time,
+            creator_fee_on: pool_state.creator_fee_on,
+            enable_creator_fee: pool_state.enable_creator_fee,
+            creator_fee_rate: pool_state.adjust_creator_fee_rate(&ctx.accounts.amm_config),
+            with_permission: true,
+        }
+    );
 
     Ok(())
 }
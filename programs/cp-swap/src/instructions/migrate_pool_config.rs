@@ -0,0 +1,285 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::utils::token::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::Mint;
+use anchor_spl::token_interface::Token2022;
+use anchor_spl::token_interface::TokenAccount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigratePoolConfig<'info> {
+    /// The admin or the pool creator, or the fee manager for a scheduled migration raising the fees
+    #[account(
+        mut,
+        constraint = (authority.key() == global_admin.admin
+            || authority.key() == pool_state.load()?.pool_creator
+            || global_admin.is_fee_manager(&authority.key())) @ ErrorCode::InvalidOwner
+    )]
+    pub authority: Signer<'info>,
+
+    /// CHECK: pool vault and lp mint authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// The pool to migrate
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the accrued fees are settled under
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The amm config the pool is moved to
+    #[account(constraint = new_amm_config.key() != amm_config.key() @ ErrorCode::InvalidInput)]
+    pub new_amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of token_0 vault
+    #[account(
+        address = token_0_vault.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token_1 vault
+    #[account(
+        address = token_1_vault.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The protocol owner's token_0 account of the old amm config
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        constraint = protocol_token_0_account.owner == amm_config.protocol_owner @ ErrorCode::InvalidOwner
+    )]
+    pub protocol_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The protocol owner's token_1 account of the old amm config
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        constraint = protocol_token_1_account.owner == amm_config.protocol_owner @ ErrorCode::InvalidOwner
+    )]
+    pub protocol_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The fund owner's token_0 account of the old amm config
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        constraint = fund_token_0_account.owner == amm_config.fund_owner @ ErrorCode::InvalidOwner
+    )]
+    pub fund_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The fund owner's token_1 account of the old amm config
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        constraint = fund_token_1_account.owner == amm_config.fund_owner @ ErrorCode::InvalidOwner
+    )]
+    pub fund_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool creator's token_0 account
+    #[account(
+        mut,
+        token::mint = vault_0_mint,
+        constraint = creator_token_0_account.owner == pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner
+    )]
+    pub creator_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The pool creator's token_1 account
+    #[account(
+        mut,
+        token::mint = vault_1_mint,
+        constraint = creator_token_1_account.owner == pool_state.load()?.pool_creator @ ErrorCode::InvalidOwner
+    )]
+    pub creator_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The SPL program to perform token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// The SPL program 2022 to perform token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Global admin account stores the admin address
+    #[account(
+        seeds = [
+            GLOBAL_ADMIN_SEED.as_bytes(),
+        ],
+        bump = global_admin.bump,
+    )]
+    pub global_admin: Box<Account<'info, GlobalAdmin>>,
+}
+
+/// The pool address stays derived from the old amm config, so a new pool of the same mints
+/// can be created under the new config, clients have to read `PoolState::amm_config`
+/// rather than derive the pool address from it.
+pub fn migrate_pool_config<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, MigratePoolConfig<'info>>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let authority = ctx.accounts.authority.key();
+    let is_fee_raise = ctx
+        .accounts
+        .pool_state
+        .load()?
+        .is_fee_raise(&ctx.accounts.amm_config, &ctx.accounts.new_amm_config);
+    if is_fee_raise {
+        // fee raises wait for the same delay as the amm config ones, scheduled by the fee manager
+        require!(
+            ctx.accounts.global_admin.is_fee_manager(&authority),
+            ErrorCode::InvalidFeeRate
+        );
+        let pool_config_migration = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(ErrorCode::ConfigChangeNotReady)?;
+        let pool_config_migration =
+            Account::<PoolConfigMigration>::try_from(pool_config_migration)?;
+        pool_config_migration.check_ready(
+            pool_id,
+            ctx.accounts.new_amm_config.key(),
+            Clock::get()?.unix_timestamp as u64,
+        )?;
+        pool_config_migration.close(ctx.accounts.authority.to_account_info())?;
+    } else if authority != ctx.accounts.global_admin.admin {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        require_keys_eq!(authority, pool_state.pool_creator, ErrorCode::InvalidOwner);
+        // the pool creator can only move to a config open for new pools, out of the configs a
+        // permission is scoped to only through the admin
+        require!(
+            !ctx.accounts.new_amm_config.disable_create_pool && !pool_state.with_permission,
+            ErrorCode::NotApproved
+        );
+    }
+
+    let protocol_fees_token_0: u64;
+    let protocol_fees_token_1: u64;
+    let fund_fees_token_0: u64;
+    let fund_fees_token_1: u64;
+    let creator_fees_token_0: u64;
+    let creator_fees_token_1: u64;
+    let auth_bump: u8;
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        protocol_fees_token_0 = pool_state.protocol_fees_token_0;
+        protocol_fees_token_1 = pool_state.protocol_fees_token_1;
+        fund_fees_token_0 = pool_state.fund_fees_token_0;
+        fund_fees_token_1 = pool_state.fund_fees_token_1;
+        // a split creator fee stays in the pool for `distribute_creator_fee`
+        if pool_state.creator_fee_split == Pubkey::default() {
+            creator_fees_token_0 = pool_state.creator_fees_token_0;
+            creator_fees_token_1 = pool_state.creator_fees_token_1;
+        } else {
+            creator_fees_token_0 = 0;
+            creator_fees_token_1 = 0;
+        }
+
+        pool_state.protocol_fees_token_0 = 0;
+        pool_state.protocol_fees_token_1 = 0;
+        pool_state.fund_fees_token_0 = 0;
+        pool_state.fund_fees_token_1 = 0;
+        pool_state.creator_fees_token_0 = pool_state
+            .creator_fees_token_0
+            .checked_sub(creator_fees_token_0)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool_state.creator_fees_token_1 = pool_state
+            .creator_fees_token_1
+            .checked_sub(creator_fees_token_1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool_state.amm_config = ctx.accounts.new_amm_config.key();
+
+        auth_bump = pool_state.auth_bump;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+    }
+
+    let token_0_program =
+        if ctx.accounts.vault_0_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    let token_1_program =
+        if ctx.accounts.vault_1_mint.to_account_info().owner == ctx.accounts.token_program.key {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.token_program_2022.to_account_info()
+        };
+    let signer_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[auth_bump]];
+
+    for (recipient, amount) in [
+        (
+            &ctx.accounts.protocol_token_0_account,
+            protocol_fees_token_0,
+        ),
+        (&ctx.accounts.fund_token_0_account, fund_fees_token_0),
+        (&ctx.accounts.creator_token_0_account, creator_fees_token_0),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.accounts.token_0_vault.to_account_info(),
+            recipient.to_account_info(),
+            ctx.accounts.vault_0_mint.to_account_info(),
+            token_0_program.clone(),
+            amount,
+            ctx.accounts.vault_0_mint.decimals,
+            &[signer_seeds],
+        )?;
+    }
+    for (recipient, amount) in [
+        (
+            &ctx.accounts.protocol_token_1_account,
+            protocol_fees_token_1,
+        ),
+        (&ctx.accounts.fund_token_1_account, fund_fees_token_1),
+        (&ctx.accounts.creator_token_1_account, creator_fees_token_1),
+    ] {
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.vault_authority.to_account_info(),
+            ctx.accounts.token_1_vault.to_account_info(),
+            recipient.to_account_info(),
+            ctx.accounts.vault_1_mint.to_account_info(),
+            token_1_program.clone(),
+            amount,
+            ctx.accounts.vault_1_mint.decimals,
+            &[signer_seeds],
+        )?;
+    }
+
+    emit_event!(
+        ctx,
+        PoolConfigMigratedEvent {
+            pool_id,
+            authority,
+            old_amm_config: ctx.accounts.amm_config.key(),
+            new_amm_config: ctx.accounts.new_amm_config.key(),
+            protocol_fees_token_0,
+            protocol_fees_token_1,
+            fund_fees_token_0,
+            fund_fees_token_1,
+            creator_fees_token_0,
+            creator_fees_token_1,
+        }
+    );
+
+    Ok(())
+}
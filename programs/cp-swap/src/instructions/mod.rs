@@ -19,3 +19,45 @@ pub use initialize_with_permission::*;
 
 pub mod collect_creator_fee;
 pub use collect_creator_fee::*;
+
+pub mod initialize_observation_history;
+pub use initialize_observation_history::*;
+
+pub mod get_lp_price;
+pub use get_lp_price::*;
+
+pub mod apply_config_change;
+pub use apply_config_change::*;
+
+pub mod transfer_pool_creator;
+pub use transfer_pool_creator::*;
+
+pub mod accept_pool_creator;
+pub use accept_pool_creator::*;
+
+pub mod set_creator_fee_split;
+pub use set_creator_fee_split::*;
+
+pub mod distribute_creator_fee;
+pub use distribute_creator_fee::*;
+
+pub mod remove_creator_fee_split;
+pub use remove_creator_fee_split::*;
+
+pub mod update_pool_creator_settings;
+pub use update_pool_creator_settings::*;
+
+pub mod migrate_pool_config;
+pub use migrate_pool_config::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod cancel_pool;
+pub use cancel_pool::*;
+
+pub mod sync;
+pub use sync::*;
+
+pub mod donate;
+pub use donate::*;
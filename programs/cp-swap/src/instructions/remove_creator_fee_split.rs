@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveCreatorFeeSplit<'info> {
+    /// Only the pool creator can remove the split, receives the rent
+    #[account(mut, address = pool_state.load()?.pool_creator)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The creator fee split of the pool, closed
+    #[account(
+        mut,
+        address = pool_state.load()?.creator_fee_split,
+        close = creator
+    )]
+    pub creator_fee_split: Account<'info, CreatorFeeSplit>,
+}
+
+pub fn remove_creator_fee_split(ctx: Context<RemoveCreatorFeeSplit>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        // the fees accrued under the split belong to its recipients, they're distributed first
+        require!(
+            pool_state.creator_fees_token_0 == 0 && pool_state.creator_fees_token_1 == 0,
+            ErrorCode::NotApproved
+        );
+        // the creator fees are collected by `collect_creator_fee` again
+        pool_state.creator_fee_split = Pubkey::default();
+    }
+
+    emit_event!(
+        ctx,
+        CreatorFeeSplitUpdatedEvent {
+            pool_id,
+            recipients: vec![],
+            shares: vec![],
+        }
+    );
+    Ok(())
+}
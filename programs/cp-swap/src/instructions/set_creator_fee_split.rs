@@ -0,0 +1,52 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCreatorFeeSplit<'info> {
+    /// Only the pool creator can split the creator fees
+    #[account(mut, address = pool_state.load()?.pool_creator)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The creator fee split of the pool, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [
+            CREATOR_FEE_SPLIT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = CreatorFeeSplit::LEN
+    )]
+    pub creator_fee_split: Account<'info, CreatorFeeSplit>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_creator_fee_split(
+    ctx: Context<SetCreatorFeeSplit>,
+    recipients: Vec<Pubkey>,
+    shares: Vec<u16>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let creator_fee_split = &mut ctx.accounts.creator_fee_split;
+    creator_fee_split.bump = ctx.bumps.creator_fee_split;
+    creator_fee_split.pool_id = pool_id;
+    creator_fee_split.set_recipients(&recipients, &shares)?;
+    // from now on the creator fees can only be distributed to the recipients
+    ctx.accounts.pool_state.load_mut()?.creator_fee_split = ctx.accounts.creator_fee_split.key();
+
+    emit_event!(
+        ctx,
+        CreatorFeeSplitUpdatedEvent {
+            pool_id,
+            recipients,
+            shares,
+        }
+    );
+    Ok(())
+}
@@ -1,13 +1,14 @@
-use super::swap_base_input::Swap;
-use crate::curve::calculator::CurveCalculator;
+use super::swap_base_input::{update_price_breaker, Swap};
+use crate::curve::calculator::{CurveCalculator, TradeDirection};
 use crate::error::ErrorCode;
 use crate::states::*;
 use crate::utils::token::*;
+use crate::utils::U128;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program;
 
-pub fn swap_base_output(
-    ctx: Context<Swap>,
+pub fn swap_base_output<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
     max_amount_in: u64,
     amount_out_received: u64,
 ) -> Result<()> {
@@ -15,7 +16,11 @@ pub fn swap_base_output(
     let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, block_timestamp)
+        || !ctx
+            .accounts
+            .amm_config
+            .get_status_by_bit(PoolStatusBitIndex::Swap)
         || block_timestamp < pool_state.open_time
     {
         return err!(ErrorCode::NotApproved);
@@ -43,8 +48,7 @@ pub fn swap_base_output(
         .checked_mul(u128::from(total_output_token_amount))
         .unwrap();
 
-    let creator_fee_rate =
-        pool_state.adjust_creator_fee_rate(ctx.accounts.amm_config.creator_fee_rate);
+    let creator_fee_rate = pool_state.adjust_creator_fee_rate(&ctx.accounts.amm_config);
     let result = CurveCalculator::swap_base_output(
         u128::from(amount_out_with_transfer_fee),
         u128::from(total_input_token_amount),
@@ -97,28 +101,90 @@ pub fn swap_base_output(
     let (output_transfer_amount, output_transfer_fee) =
         (amount_out_with_transfer_fee, out_transfer_fee);
 
+    if let Some(event) = update_price_breaker(
+        pool_id,
+        pool_state,
+        &ctx.accounts.amm_config,
+        block_timestamp,
+        trade_direction,
+        token_0_price_x64,
+        &result,
+    )? {
+        emit_event!(ctx, event);
+    }
+
     pool_state.update_fees(
-        u64::try_from(result.protocol_fee).unwrap(),
-        u64::try_from(result.fund_fee).unwrap(),
-        u64::try_from(result.creator_fee).unwrap(),
+        u64::try_from(result.protocol_fee).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(result.fund_fee).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(result.creator_fee).map_err(|_| ErrorCode::MathOverflow)?,
         trade_direction,
     )?;
 
-    emit!(SwapEvent {
-        pool_id,
-        input_vault_before: total_input_token_amount,
-        output_vault_before: total_output_token_amount,
-        input_amount: u64::try_from(result.input_amount).unwrap(),
-        output_amount: u64::try_from(result.output_amount).unwrap(),
-        input_transfer_fee,
-        output_transfer_fee,
-        base_input: false,
-        input_mint: ctx.accounts.input_token_mint.key(),
-        output_mint: ctx.accounts.output_token_mint.key(),
-        trade_fee: u64::try_from(result.trade_fee).unwrap(),
-        creator_fee: u64::try_from(result.creator_fee).unwrap(),
-        creator_fee_on_input: is_creator_fee_on_input,
-    });
+    emit_event!(
+        ctx,
+        SwapEvent {
+            pool_id,
+            input_vault_before: total_input_token_amount,
+            output_vault_before: total_output_token_amount,
+            input_amount: u64::try_from(result.input_amount)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            output_amount: u64::try_from(result.output_amount)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            input_transfer_fee,
+            output_transfer_fee,
+            base_input: false,
+            input_mint: ctx.accounts.input_token_mint.key(),
+            output_mint: ctx.accounts.output_token_mint.key(),
+            trade_fee: u64::try_from(result.trade_fee).map_err(|_| ErrorCode::MathOverflow)?,
+            creator_fee: u64::try_from(result.creator_fee).map_err(|_| ErrorCode::MathOverflow)?,
+            creator_fee_on_input: is_creator_fee_on_input,
+        }
+    );
+
+    let (input_vault_after, output_vault_after) =
+        result.reserves_after().ok_or(ErrorCode::MathOverflow)?;
+    let (token_0_vault_after, token_1_vault_after) = match trade_direction {
+        TradeDirection::ZeroForOne => (input_vault_after, output_vault_after),
+        TradeDirection::OneForZero => (output_vault_after, input_vault_after),
+    };
+    pool_state.set_reserves(
+        u64::try_from(token_0_vault_after).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(token_1_vault_after).map_err(|_| ErrorCode::MathOverflow)?,
+    );
+    emit_event!(
+        ctx,
+        SwapEventV2 {
+            pool_id,
+            input_mint: ctx.accounts.input_token_mint.key(),
+            output_mint: ctx.accounts.output_token_mint.key(),
+            base_input: false,
+            input_vault_before: total_input_token_amount,
+            output_vault_before: total_output_token_amount,
+            input_vault_after: u64::try_from(input_vault_after)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            output_vault_after: u64::try_from(output_vault_after)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            input_amount: u64::try_from(result.input_amount)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            output_amount: u64::try_from(result.output_amount)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            input_transfer_fee,
+            output_transfer_fee,
+            trade_fee: u64::try_from(result.trade_fee).map_err(|_| ErrorCode::MathOverflow)?,
+            protocol_fee: u64::try_from(result.protocol_fee)
+                .map_err(|_| ErrorCode::MathOverflow)?,
+            fund_fee: u64::try_from(result.fund_fee).map_err(|_| ErrorCode::MathOverflow)?,
+            creator_fee: u64::try_from(result.creator_fee).map_err(|_| ErrorCode::MathOverflow)?,
+            creator_fee_on_input: is_creator_fee_on_input,
+            trade_fee_rate: ctx.accounts.amm_config.trade_fee_rate,
+            protocol_fee_rate: ctx.accounts.amm_config.protocol_fee_rate,
+            fund_fee_rate: ctx.accounts.amm_config.fund_fee_rate,
+            creator_fee_rate,
+            lp_supply: pool_state.lp_supply,
+            token_0_price_x64: token_1_vault_after * Q64 / token_0_vault_after,
+            token_1_price_x64: token_0_vault_after * Q64 / token_1_vault_after,
+        }
+    );
     require_gte!(constant_after, constant_before);
 
     transfer_from_user_to_pool_vault(
@@ -143,10 +209,20 @@ pub fn swap_base_output(
     )?;
 
     // update the previous price to the observation
-    ctx.accounts.observation_state.load_mut()?.update(
+    let (token_0_volume, token_1_volume) = match trade_direction {
+        TradeDirection::ZeroForOne => (result.input_amount, result.output_amount),
+        TradeDirection::OneForZero => (result.output_amount, result.input_amount),
+    };
+    let observation_history =
+        load_observation_history(ctx.remaining_accounts, pool_state.observation_history_key)?;
+    PoolObservation::try_from(&ctx.accounts.observation_state.to_account_info())?.update(
         oracle::block_timestamp(),
         token_0_price_x64,
         token_1_price_x64,
+        u64::try_from(token_0_volume).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(token_1_volume).map_err(|_| ErrorCode::MathOverflow)?,
+        U128::from(constant_before).integer_sqrt().as_u64(),
+        observation_history.as_ref(),
     )?;
     pool_state.recent_epoch = Clock::get()?.epoch;
 
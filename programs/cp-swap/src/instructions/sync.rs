@@ -0,0 +1,135 @@
+use crate::error::ErrorCode;
+use crate::instructions::check_price_breaker;
+use crate::states::*;
+use crate::utils::U128;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    /// Anyone can sync the reserves of an open pool, only the pool creator before it opens
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        constraint = token_0_vault.key() == pool_state.load()?.token_0_vault
+    )]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        constraint = token_1_vault.key() == pool_state.load()?.token_1_vault
+    )]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Amm config account stores the status and the price breaker
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: the oracle of the pool, ObservationState or ObservationStateV2
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
+}
+
+pub fn sync<'c: 'info, 'info>(ctx: Context<'_, '_, 'c, 'info, SyncReserves<'info>>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let block_timestamp = oracle::block_timestamp();
+    let (reserve_0_before, reserve_1_before, reserve_0, reserve_1, price_breaker_event) = {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, block_timestamp)
+            || !ctx
+                .accounts
+                .amm_config
+                .get_status_by_bit(PoolStatusBitIndex::Deposit)
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        // the tokens sent to the vaults before the pool opens can only be absorbed by its creator
+        if block_timestamp < pool_state.open_time
+            && ctx.accounts.payer.key() != pool_state.pool_creator
+        {
+            return err!(ErrorCode::NotApproved);
+        }
+        let (reserve_0_before, reserve_1_before) = pool_state.vault_amount_without_fee(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        )?;
+        update_observation_before_reserves_change(
+            &pool_state,
+            &ctx.accounts.observation_state.to_account_info(),
+            ctx.remaining_accounts,
+            reserve_0_before,
+            reserve_1_before,
+        )?;
+        pool_state.sync_reserves(
+            ctx.accounts.token_0_vault.amount,
+            ctx.accounts.token_1_vault.amount,
+        )?;
+        let (reserve_0, reserve_1) = (pool_state.reserve_0, pool_state.reserve_1);
+        // tokens sent to a single vault move the price like a swap does
+        let (trade_direction, token_0_price_x64, price_x64) =
+            reserves_change_price_x64(reserve_0_before, reserve_1_before, reserve_0, reserve_1)?;
+        let price_breaker_event = check_price_breaker(
+            pool_id,
+            &mut pool_state,
+            &ctx.accounts.amm_config,
+            block_timestamp,
+            trade_direction,
+            token_0_price_x64,
+            price_x64,
+        )?;
+        pool_state.recent_epoch = Clock::get()?.epoch;
+        (
+            reserve_0_before,
+            reserve_1_before,
+            reserve_0,
+            reserve_1,
+            price_breaker_event,
+        )
+    };
+
+    if let Some(event) = price_breaker_event {
+        emit_event!(ctx, event);
+    }
+    emit_event!(
+        ctx,
+        ReservesSyncedEvent {
+            pool_id,
+            reserve_0_before,
+            reserve_1_before,
+            reserve_0,
+            reserve_1,
+        }
+    );
+    Ok(())
+}
+
+/// Writes the price held until now to the oracle before the reserves change outside of a swap,
+/// so that the new price isn't credited to the time since the last observation
+pub(crate) fn update_observation_before_reserves_change<'info>(
+    pool_state: &PoolState,
+    observation_state: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    reserve_0: u64,
+    reserve_1: u64,
+) -> Result<()> {
+    if reserve_0 == 0 || reserve_1 == 0 {
+        return Ok(());
+    }
+    let (reserve_0, reserve_1) = (u128::from(reserve_0), u128::from(reserve_1));
+    let observation_history =
+        load_observation_history(remaining_accounts, pool_state.observation_history_key)?;
+    PoolObservation::try_from(observation_state)?.update(
+        oracle::block_timestamp(),
+        reserve_1 * Q64 / reserve_0,
+        reserve_0 * Q64 / reserve_1,
+        0,
+        0,
+        U128::from(reserve_0 * reserve_1).integer_sqrt().as_u64(),
+        observation_history.as_ref(),
+    )
+}
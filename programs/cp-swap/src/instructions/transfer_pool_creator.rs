@@ -0,0 +1,29 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferPoolCreator<'info> {
+    /// Only the pool creator can transfer the role
+    #[account(address = pool_state.load()?.pool_creator)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn transfer_pool_creator(ctx: Context<TransferPoolCreator>, new_creator: Pubkey) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.propose_pool_creator(new_creator);
+
+    emit_event!(
+        ctx,
+        PoolCreatorChangeEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            old_creator: pool_state.pool_creator,
+            new_creator,
+            pending: true,
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePoolCreatorSettings<'info> {
+    /// Only the pool creator can update the settings
+    #[account(address = pool_state.load()?.pool_creator)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config bounding the creator fee rate
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+pub fn update_pool_creator_settings(
+    ctx: Context<UpdatePoolCreatorSettings>,
+    update: PoolCreatorSettingsUpdate,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let old_value = pool_state.update_creator_settings(
+        update.clone(),
+        &ctx.accounts.amm_config,
+        Clock::get()?.unix_timestamp as u64,
+    )?;
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    emit_event!(
+        ctx,
+        PoolCreatorSettingsUpdatedEvent {
+            pool_id: ctx.accounts.pool_state.key(),
+            old_value,
+            new_value: update,
+        }
+    );
+    Ok(())
+}
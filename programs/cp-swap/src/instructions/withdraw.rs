@@ -10,6 +10,7 @@ use anchor_spl::{
     token_interface::{Mint, Token2022, TokenAccount},
 };
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     /// Pays to mint the position
@@ -94,6 +95,14 @@ pub struct Withdraw<'info> {
         address = spl_memo::id()
     )]
     pub memo_program: UncheckedAccount<'info>,
+
+    /// Amm config account stores the config-wide status and the price deviation guard
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// CHECK: the oracle of the pool, ObservationState or ObservationStateV2
+    #[account(address = pool_state.load()?.observation_key)]
+    pub observation_state: UncheckedAccount<'info>,
 }
 
 pub fn withdraw(
@@ -106,9 +115,33 @@ pub fn withdraw(
     require_gte!(ctx.accounts.owner_lp_token.amount, lp_token_amount);
     let pool_id = ctx.accounts.pool_state.key();
     let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw) {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw, oracle::block_timestamp()) {
+        return err!(ErrorCode::NotApproved);
+    }
+    if !ctx
+        .accounts
+        .amm_config
+        .get_status_by_bit(PoolStatusBitIndex::Withdraw)
+    {
         return err!(ErrorCode::NotApproved);
     }
+    let (token_0_price_x64, token_1_price_x64) = pool_state.token_price_x64(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    // withdrawals aren't guarded unless the config opts in, so LPs can always exit
+    PoolObservation::try_from(&ctx.accounts.observation_state.to_account_info())?
+        .check_price_deviation(
+            oracle::block_timestamp(),
+            ctx.accounts.amm_config.price_deviation_window(),
+            token_0_price_x64,
+            token_1_price_x64,
+            if ctx.accounts.amm_config.price_deviation_on_withdraw {
+                ctx.accounts.amm_config.max_price_deviation_rate
+            } else {
+                0
+            },
+        )?;
     let (total_token_0_amount, total_token_1_amount) = pool_state.vault_amount_without_fee(
         ctx.accounts.token_0_vault.amount,
         ctx.accounts.token_1_vault.amount,
@@ -157,17 +190,20 @@ pub fn withdraw(
         receive_token_1_amount,
         token_1_transfer_fee
     );
-    emit!(LpChangeEvent {
-        pool_id,
-        lp_amount_before: pool_state.lp_supply,
-        token_0_vault_before: total_token_0_amount,
-        token_1_vault_before: total_token_1_amount,
-        token_0_amount: receive_token_0_amount,
-        token_1_amount: receive_token_1_amount,
-        token_0_transfer_fee,
-        token_1_transfer_fee,
-        change_type: 1
-    });
+    emit_event!(
+        ctx,
+        LpChangeEvent {
+            pool_id,
+            lp_amount_before: pool_state.lp_supply,
+            token_0_vault_before: total_token_0_amount,
+            token_1_vault_before: total_token_1_amount,
+            token_0_amount: receive_token_0_amount,
+            token_1_amount: receive_token_1_amount,
+            token_0_transfer_fee,
+            token_1_transfer_fee,
+            change_type: 1
+        }
+    );
 
     if receive_token_0_amount < minimum_token_0_amount
         || receive_token_1_amount < minimum_token_1_amount
@@ -176,6 +212,10 @@ pub fn withdraw(
     }
 
     pool_state.lp_supply = pool_state.lp_supply.checked_sub(lp_token_amount).unwrap();
+    pool_state.set_reserves(
+        total_token_0_amount.checked_sub(token_0_amount).unwrap(),
+        total_token_1_amount.checked_sub(token_1_amount).unwrap(),
+    );
     token_burn(
         ctx.accounts.owner.to_account_info(),
         ctx.accounts.token_program.to_account_info(),
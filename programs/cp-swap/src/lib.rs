@@ -1,12 +1,24 @@
+/// Emits the event with a self-CPI, so it's kept in the inner instructions instead of
+/// the logs which RPC nodes truncate. The instruction accounts need `#[event_cpi]`.
+/// With the `event-log` feature the event is also written to the logs.
+macro_rules! emit_event {
+    ($ctx:expr, $event:expr) => {{
+        let ctx = &$ctx;
+        let event = $event;
+        #[cfg(feature = "event-log")]
+        anchor_lang::prelude::emit!(event);
+        anchor_lang::prelude::emit_cpi!(event);
+    }};
+}
+
 pub mod curve;
 pub mod error;
 pub mod instructions;
 pub mod states;
 pub mod utils;
-use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
 use anchor_lang::prelude::*;
 use instructions::*;
-pub use states::CreatorFeeOn;
+pub use states::{AmmConfigUpdate, CreatorFeeOn, PoolCreatorSettingsUpdate};
 
 #[cfg(not(feature = "no-entrypoint"))]
 solana_security_txt::security_txt! {
@@ -24,6 +36,7 @@ declare_id!("DRaycpLY18LhpbydsBWbVJtxpNv9oXPgjRSfpF2bWpYb");
 #[cfg(not(feature = "devnet"))]
 declare_id!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
 
+/// The bootstrap admin creating the `GlobalAdmin` account, which holds the current admin
 pub mod admin {
     use super::{pubkey, Pubkey};
     #[cfg(feature = "devnet")]
@@ -64,10 +77,6 @@ pub mod raydium_cp_swap {
         create_pool_fee: u64,
         creator_fee_rate: u64,
     ) -> Result<()> {
-        assert!(trade_fee_rate + creator_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
-        assert!(protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-        assert!(fund_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
-        assert!(fund_fee_rate + protocol_fee_rate <= FEE_RATE_DENOMINATOR_VALUE);
         instructions::create_amm_config(
             ctx,
             index,
@@ -79,24 +88,64 @@ pub mod raydium_cp_swap {
         )
     }
 
-    /// Updates the owner of the amm config
-    /// Must be called by the current owner or admin
+    /// Updates one field of the amm config
+    /// Must be called by the admin, or the fee manager for the fee rates
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    /// * `trade_fee_rate`- The new trade fee rate of amm config, be set when `param` is 0
-    /// * `protocol_fee_rate`- The new protocol fee rate of amm config, be set when `param` is 1
-    /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
-    /// * `new_owner`- The config's new owner, be set when `param` is 3
-    /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `update`- The field to update and its new value
+    ///
+    /// Raising the trade, protocol or creator fee rate has to go through `schedule_config_change`
+    ///
+    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, update: AmmConfigUpdate) -> Result<()> {
+        instructions::update_amm_config(ctx, update)
+    }
+
+    /// Schedule a trade, protocol or creator fee rate change, raises can only be made this way
     ///
-    pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u64) -> Result<()> {
-        instructions::update_amm_config(ctx, param, value)
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `update`- The new trade, protocol or creator fee rate
+    /// * `effective_time`- The timestamp from which the change can be applied, at least
+    ///   `CONFIG_CHANGE_MIN_DELAY` from now, 0 to cancel the scheduled change
+    ///
+    pub fn schedule_config_change(
+        ctx: Context<UpdateAmmConfig>,
+        update: AmmConfigUpdate,
+        effective_time: u64,
+    ) -> Result<()> {
+        instructions::schedule_config_change(ctx, update, effective_time)
     }
 
-    /// Update pool status for given value
+    /// Schedule the migration of a pool to an amm config with higher fees, by the fee manager.
+    /// The fee manager applies it with `migrate_pool_config` once effective
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `effective_time`- The timestamp from which the migration can be applied, at least
+    ///   `CONFIG_CHANGE_MIN_DELAY` from now
+    ///
+    pub fn schedule_pool_config_migration(
+        ctx: Context<SchedulePoolConfigMigration>,
+        effective_time: u64,
+    ) -> Result<()> {
+        instructions::schedule_pool_config_migration(ctx, effective_time)
+    }
+
+    /// Apply the scheduled change of an amm config once effective, anyone can call it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn apply_config_change(ctx: Context<ApplyConfigChange>) -> Result<()> {
+        instructions::apply_config_change(ctx)
+    }
+
+    /// Update pool status for given value, the pause guardian can only set the disabled bits
     ///
     /// # Arguments
     ///
@@ -107,6 +156,37 @@ pub mod raydium_cp_swap {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Disable the status bits of a pool until a timestamp, after which they're enabled again.
+    /// The pause guardian can't shorten a pause in effect
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `status` - The status bits to disable
+    /// * `pause_until` - The timestamp the bits are disabled until
+    /// * `reason` - The reason code, only emitted in the event
+    ///
+    pub fn pause_pool(
+        ctx: Context<UpdatePoolStatus>,
+        status: u8,
+        pause_until: u64,
+        reason: u8,
+    ) -> Result<()> {
+        instructions::pause_pool(ctx, status, pause_until, reason)
+    }
+
+    /// Update the status of every pool of an amm config, checked alongside each pool's own status.
+    /// The pause guardian can only set the disabled bits
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `status` - The value of status, same bits as the pool status
+    ///
+    pub fn update_amm_config_status(ctx: Context<UpdateAmmConfigStatus>, status: u8) -> Result<()> {
+        instructions::update_amm_config_status(ctx, status)
+    }
+
     /// Collect the protocol fee accrued to the pool
     ///
     /// # Arguments
@@ -149,14 +229,201 @@ pub mod raydium_cp_swap {
         instructions::collect_creator_fee(ctx)
     }
 
+    /// Donate tokens to the liquidity providers of the pool without minting lp tokens.
+    /// Only the pool creator can donate before the pool opens.
+    /// Arbitrary amounts move the price and go through the price movement circuit breaker
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the observation history account of the pool has to follow if it has one
+    /// * `token_0_amount` - The amount of token_0 the vault receives, the transfer fee is paid on top
+    /// * `token_1_amount` - The amount of token_1 the vault receives, the transfer fee is paid on top
+    /// * `proportional` - Whether to scale down the amounts to the ratio of the reserves, keeping the price unchanged
+    ///
+    pub fn donate<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Donate<'info>>,
+        token_0_amount: u64,
+        token_1_amount: u64,
+        proportional: bool,
+    ) -> Result<()> {
+        instructions::donate(ctx, token_0_amount, token_1_amount, proportional)
+    }
+
+    /// Absorb the tokens sent directly to the vaults into the tracked reserves of the pool.
+    /// Needs the deposit status, only the pool creator can sync before the pool opens,
+    /// and the price moved by the absorbed tokens goes through the price movement circuit breaker
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the observation history account of the pool has to follow if it has one
+    ///
+    pub fn sync<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, SyncReserves<'info>>,
+    ) -> Result<()> {
+        instructions::sync(ctx)
+    }
+
+    /// Send the tokens sent directly to the vaults, which are neither reserves nor fees, to a recipient
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn skim(ctx: Context<Skim>) -> Result<()> {
+        instructions::skim(ctx)
+    }
+
+    /// Propose a new pool creator, which receives the creator fees once accepted
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `new_creator` - The proposed pool creator, default to cancel a pending transfer
+    ///
+    pub fn transfer_pool_creator(
+        ctx: Context<TransferPoolCreator>,
+        new_creator: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_pool_creator(ctx, new_creator)
+    }
+
+    /// Accept the pool creator role, signed by the proposed pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn accept_pool_creator(ctx: Context<AcceptPoolCreator>) -> Result<()> {
+        instructions::accept_pool_creator(ctx)
+    }
+
+    /// Split the creator fees among up to 5 recipients, after which they can only be
+    /// distributed to them by `distribute_creator_fee`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `recipients` - The owners of the token accounts receiving the creator fees
+    /// * `shares` - The shares of the recipients in basis points, summing to 10000
+    ///
+    pub fn set_creator_fee_split(
+        ctx: Context<SetCreatorFeeSplit>,
+        recipients: Vec<Pubkey>,
+        shares: Vec<u16>,
+    ) -> Result<()> {
+        instructions::set_creator_fee_split(ctx, recipients, shares)
+    }
+
+    /// Distribute the creator fees of a pool to the recipients of its split, anyone can call it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the remaining accounts are the token_0 and token_1
+    ///   accounts of each recipient
+    ///
+    pub fn distribute_creator_fee<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DistributeCreatorFee<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_creator_fee(ctx)
+    }
+
+    /// Remove the creator fee split of a pool once its fees are distributed, the creator collects the fees again
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn remove_creator_fee_split(ctx: Context<RemoveCreatorFeeSplit>) -> Result<()> {
+        instructions::remove_creator_fee_split(ctx)
+    }
+
+    /// Update a setting of a pool, signed by the pool creator. The creator fee rate can only be
+    /// lowered, the creator fee only turned off, and the open time only delayed before opening, by a week at most
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `update` - The new value of the setting
+    ///
+    pub fn update_pool_creator_settings(
+        ctx: Context<UpdatePoolCreatorSettings>,
+        update: PoolCreatorSettingsUpdate,
+    ) -> Result<()> {
+        instructions::update_pool_creator_settings(ctx, update)
+    }
+
+    /// Move a pool to another amm config, signed by the admin or the pool creator.
+    /// The accrued protocol, fund and creator fees are paid out under the old config first.
+    /// The pool creator can't move a pool created with a permission.
+    /// Raising the trade, protocol or creator fee rate has to go through `schedule_pool_config_migration`.
+    /// The pool address stays derived from the old config
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the scheduled `PoolConfigMigration` follows for a fee raise
+    ///
+    pub fn migrate_pool_config<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MigratePoolConfig<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_pool_config(ctx)
+    }
+
+    /// Close a pool whose liquidity is all withdrawn and fees are all collected.
+    /// The dust left in the vaults goes to the protocol and the rent to the pool creator
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the observation history and the creator fee split accounts of the pool
+    ///   follow in this order if it has them
+    ///
+    pub fn close_pool<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClosePool<'info>>,
+    ) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    /// Cancel a pool before its open time, signed by the pool creator holding the whole lp supply,
+    /// only the pool creator can deposit before the open time. The liquidity and the rent are returned
+    /// to the pool creator, the lp mint is left to be reused if the pool is created again
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the observation history and the creator fee split accounts of the pool
+    ///   follow in this order if it has them
+    ///
+    pub fn cancel_pool<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CancelPool<'info>>,
+    ) -> Result<()> {
+        instructions::cancel_pool(ctx)
+    }
+
     /// Create a permission account
     ///
     /// # Arguments
     ///
     /// * `ctx`- The context of accounts
-    ///
-    pub fn create_permission_pda(ctx: Context<CreatePermissionPda>) -> Result<()> {
-        instructions::create_permission_pda(ctx)
+    /// * `allowed_amm_configs`- The amm configs pools can be created with, at most 4, any if empty
+    /// * `expire_time`- The timestamp the permission expires at, 0 if it doesn't expire
+    /// * `max_pool_count`- Max number of pools to be created, 0 if unlimited
+    /// * `max_creator_fee_rate`- Max creator fee rate of the pools, 0 if unlimited
+    /// * `allowed_creator_fee_on`- Bitmask of the allowed `CreatorFeeOn` modes, all modes if 0
+    ///
+    pub fn create_permission_pda(
+        ctx: Context<CreatePermissionPda>,
+        allowed_amm_configs: Vec<Pubkey>,
+        expire_time: u64,
+        max_pool_count: u64,
+        max_creator_fee_rate: u64,
+        allowed_creator_fee_on: u8,
+    ) -> Result<()> {
+        instructions::create_permission_pda(
+            ctx,
+            allowed_amm_configs,
+            expire_time,
+            max_pool_count,
+            max_creator_fee_rate,
+            allowed_creator_fee_on,
+        )
     }
 
     /// Close a permission account
@@ -169,6 +436,53 @@ pub mod raydium_cp_swap {
         instructions::close_permission_pda(ctx)
     }
 
+    /// Create the global admin account, the bootstrap admin becomes its first admin
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn create_global_admin(ctx: Context<CreateGlobalAdmin>) -> Result<()> {
+        instructions::create_global_admin(ctx)
+    }
+
+    /// Propose a new admin, who must accept it to take over
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `new_admin` - The proposed admin, the default address cancels the proposal
+    ///
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    /// Accept the admin role proposed to the signer
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    /// Assign an admin role, the default address revokes it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `role`- 0: pause guardian, can only disable pools, 1: fee manager, changes the fee rates
+    /// * `account`- The new holder of the role
+    ///
+    pub fn update_admin_role(
+        ctx: Context<UpdateAdminRole>,
+        role: u8,
+        account: Pubkey,
+    ) -> Result<()> {
+        instructions::update_admin_role(ctx, role, account)
+    }
+
     /// Creates a pool for the given token pair and the initial price
     ///
     /// # Arguments
@@ -196,6 +510,7 @@ pub mod raydium_cp_swap {
     /// * `init_amount_1` - the initial amount_1 to deposit
     /// * `open_time` - the timestamp allowed for swap
     /// * `creator_fee_on` - creator fee model, 0：both token0 and token1 (depends on the input), 1: only token0, 2: only token1
+    /// * `creator_fee_rate` - the creator fee rate of the pool, at most the amm config max, 0 to inherit the amm config one
     ///
     pub fn initialize_with_permission(
         ctx: Context<InitializeWithPermission>,
@@ -203,6 +518,7 @@ pub mod raydium_cp_swap {
         init_amount_1: u64,
         open_time: u64,
         creator_fee_on: CreatorFeeOn,
+        creator_fee_rate: u64,
     ) -> Result<()> {
         instructions::initialize_with_permission(
             ctx,
@@ -210,9 +526,34 @@ pub mod raydium_cp_swap {
             init_amount_1,
             open_time,
             creator_fee_on,
+            creator_fee_rate,
         )
     }
 
+    /// Create the optional hourly oracle history account of a pool
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn initialize_observation_history(
+        ctx: Context<InitializeObservationHistory>,
+    ) -> Result<()> {
+        instructions::initialize_observation_history(ctx)
+    }
+
+    /// Returns the manipulation-resistant value of one LP token unit in return data,
+    /// priced from the fair reserves at the pool's TWAP
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `window` - The number of seconds of the TWAP, can't be zero
+    ///
+    pub fn get_lp_price(ctx: Context<GetLpPrice>, window: u64) -> Result<LpPrice> {
+        instructions::get_lp_price(ctx, window)
+    }
+
     /// Deposit lp token to the pool
     ///
     /// # Arguments
@@ -267,8 +608,8 @@ pub mod raydium_cp_swap {
     /// * `amount_in` -  input amount to transfer, output to DESTINATION is based on the exchange rate
     /// * `minimum_amount_out` -  Minimum amount of output token, prevents excessive slippage
     ///
-    pub fn swap_base_input(
-        ctx: Context<Swap>,
+    pub fn swap_base_input<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
         amount_in: u64,
         minimum_amount_out: u64,
     ) -> Result<()> {
@@ -283,7 +624,11 @@ pub mod raydium_cp_swap {
     /// * `max_amount_in` -  input amount prevents excessive slippage
     /// * `amount_out` -  amount of output token
     ///
-    pub fn swap_base_output(ctx: Context<Swap>, max_amount_in: u64, amount_out: u64) -> Result<()> {
+    pub fn swap_base_output<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Swap<'info>>,
+        max_amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
         instructions::swap_base_output(ctx, max_amount_in, amount_out)
     }
 }
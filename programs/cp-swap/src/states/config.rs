@@ -1,6 +1,16 @@
+use super::{
+    PoolStatusBitIndex, LOCK_LP_AMOUNT, MAX_LOCK_LP_AMOUNT, MAX_LOCK_LP_RATE, OBSERVATION_NUM,
+    OBSERVATION_UPDATE_DURATION_DEFAULT,
+};
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
+use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
+/// TWAP window used by the price deviation guard when the config doesn't set one
+pub const DEFAULT_PRICE_DEVIATION_WINDOW: u64 = 60;
+/// Min delay between scheduling a fee rate raise and applying it
+pub const CONFIG_CHANGE_MIN_DELAY: u64 = 24 * 60 * 60;
 
 /// Holds the current owner of the factory
 #[account]
@@ -26,10 +36,559 @@ pub struct AmmConfig {
     pub fund_owner: Pubkey,
     /// The pool creator fee, denominated in hundredths of a bip (10^-6)
     pub creator_fee_rate: u64,
+    /// The max deviation of the spot price from the TWAP to deposit or withdraw,
+    /// denominated in hundredths of a bip (10^-6), 0 to disable the check
+    pub max_price_deviation_rate: u64,
+    /// The TWAP window in seconds of the price deviation check
+    pub price_deviation_window: u64,
+    /// The max price movement of a pool inside a window before swaps moving the price further
+    /// in the same direction are rejected, denominated in hundredths of a bip (10^-6), 0 to disable
+    pub max_price_move_rate: u64,
+    /// The price movement window in seconds, 0 for a single slot
+    pub price_move_window: u64,
+    /// The queued fee rate, see `AmmConfigUpdate::fee_rate_param`
+    pub pending_change_param: u8,
+    /// Bitwise representation of the state of every pool of the config, same bits as the pool status
+    /// bit0, 1: disable deposit(value is 1), 0: normal
+    /// bit1, 1: disable withdraw(value is 2), 0: normal
+    /// bit2, 1: disable swap(value is 4), 0: normal
+    pub status: u8,
+    /// Whether the price deviation check also applies to withdrawals
+    pub price_deviation_on_withdraw: bool,
+    pub padding1: [u8; 5],
+    /// The value of the queued change
+    pub pending_change_value: u64,
+    /// The timestamp from which the queued change can be applied, 0 if no change is queued
+    pub pending_change_effective_time: u64,
+    /// The max creator fee rate a pool can be created with, denominated in hundredths of a bip (10^-6),
+    /// 0 to cap it to `creator_fee_rate`
+    pub max_creator_fee_rate: u64,
+    /// The min lp amount locked forever when a pool is created, 0 for `LOCK_LP_AMOUNT`
+    pub lock_lp_amount: u64,
+    /// The share of the initial liquidity locked forever when a pool is created if it's more than
+    /// `lock_lp_amount`, denominated in hundredths of a bip (10^-6), 0 to lock `lock_lp_amount` only
+    pub lock_lp_rate: u64,
     /// padding
-    pub padding: [u64; 15],
+    pub padding: [u64; 5],
 }
 
 impl AmmConfig {
-    pub const LEN: usize = 8 + 1 + 1 + 2 + 4 * 8 + 32 * 2 + 8 + 8 * 15;
+    pub const LEN: usize = 8
+        + 1
+        + 1
+        + 2
+        + 4 * 8
+        + 32 * 2
+        + 8
+        + 8 * 2
+        + 8 * 2
+        + 1
+        + 1
+        + 1
+        + 5
+        + 8 * 2
+        + 8
+        + 8
+        + 8
+        + 8 * 5;
+
+    /// Get status by bit, if it is `noraml` status, return true
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        let status = 1u8 << (bit as u8);
+        self.status & status == 0
+    }
+
+    /// Checks the fee rates and the guards of the config are consistent
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.trade_fee_rate
+                .saturating_add(self.creator_fee_rate.max(self.max_creator_fee_rate))
+                < FEE_RATE_DENOMINATOR_VALUE,
+            ErrorCode::InvalidFeeRate
+        );
+        require!(
+            self.protocol_fee_rate.saturating_add(self.fund_fee_rate) <= FEE_RATE_DENOMINATOR_VALUE,
+            ErrorCode::InvalidFeeRate
+        );
+        require!(
+            self.max_price_deviation_rate <= FEE_RATE_DENOMINATOR_VALUE
+                && self.max_price_move_rate <= FEE_RATE_DENOMINATOR_VALUE,
+            ErrorCode::InvalidFeeRate
+        );
+        require!(
+            self.lock_lp_amount <= MAX_LOCK_LP_AMOUNT && self.lock_lp_rate <= MAX_LOCK_LP_RATE,
+            ErrorCode::InvalidInput
+        );
+        // the observation ring must still cover the window
+        require_gte!(
+            OBSERVATION_NUM as u64 * OBSERVATION_UPDATE_DURATION_DEFAULT,
+            self.price_deviation_window,
+            ErrorCode::InvalidInput
+        );
+        Ok(())
+    }
+
+    /// Returns the update restoring the current value of the updated field
+    pub fn current_value(&self, update: &AmmConfigUpdate) -> AmmConfigUpdate {
+        match update {
+            AmmConfigUpdate::TradeFeeRate(_) => AmmConfigUpdate::TradeFeeRate(self.trade_fee_rate),
+            AmmConfigUpdate::ProtocolFeeRate(_) => {
+                AmmConfigUpdate::ProtocolFeeRate(self.protocol_fee_rate)
+            }
+            AmmConfigUpdate::FundFeeRate(_) => AmmConfigUpdate::FundFeeRate(self.fund_fee_rate),
+            AmmConfigUpdate::ProtocolOwner(_) => {
+                AmmConfigUpdate::ProtocolOwner(self.protocol_owner)
+            }
+            AmmConfigUpdate::FundOwner(_) => AmmConfigUpdate::FundOwner(self.fund_owner),
+            AmmConfigUpdate::CreatePoolFee(_) => {
+                AmmConfigUpdate::CreatePoolFee(self.create_pool_fee)
+            }
+            AmmConfigUpdate::DisableCreatePool(_) => {
+                AmmConfigUpdate::DisableCreatePool(self.disable_create_pool)
+            }
+            AmmConfigUpdate::CreatorFeeRate(_) => {
+                AmmConfigUpdate::CreatorFeeRate(self.creator_fee_rate)
+            }
+            AmmConfigUpdate::MaxPriceDeviationRate(_) => {
+                AmmConfigUpdate::MaxPriceDeviationRate(self.max_price_deviation_rate)
+            }
+            AmmConfigUpdate::PriceDeviationWindow(_) => {
+                AmmConfigUpdate::PriceDeviationWindow(self.price_deviation_window)
+            }
+            AmmConfigUpdate::MaxPriceMoveRate(_) => {
+                AmmConfigUpdate::MaxPriceMoveRate(self.max_price_move_rate)
+            }
+            AmmConfigUpdate::PriceMoveWindow(_) => {
+                AmmConfigUpdate::PriceMoveWindow(self.price_move_window)
+            }
+            AmmConfigUpdate::MaxCreatorFeeRate(_) => {
+                AmmConfigUpdate::MaxCreatorFeeRate(self.max_creator_fee_rate)
+            }
+            AmmConfigUpdate::LockLpAmount(_) => AmmConfigUpdate::LockLpAmount(self.lock_lp_amount),
+            AmmConfigUpdate::PriceDeviationOnWithdraw(_) => {
+                AmmConfigUpdate::PriceDeviationOnWithdraw(self.price_deviation_on_withdraw)
+            }
+            AmmConfigUpdate::LockLpRate(_) => AmmConfigUpdate::LockLpRate(self.lock_lp_rate),
+        }
+    }
+
+    /// Sets the updated field and validates the config, returns the previous value
+    pub fn apply_update(&mut self, update: AmmConfigUpdate) -> Result<AmmConfigUpdate> {
+        let old_value = self.current_value(&update);
+        let mut updated = self.clone();
+        match update {
+            AmmConfigUpdate::TradeFeeRate(value) => updated.trade_fee_rate = value,
+            AmmConfigUpdate::ProtocolFeeRate(value) => updated.protocol_fee_rate = value,
+            AmmConfigUpdate::FundFeeRate(value) => updated.fund_fee_rate = value,
+            AmmConfigUpdate::ProtocolOwner(value) => {
+                require_keys_neq!(value, Pubkey::default(), ErrorCode::InvalidInput);
+                updated.protocol_owner = value;
+            }
+            AmmConfigUpdate::FundOwner(value) => {
+                require_keys_neq!(value, Pubkey::default(), ErrorCode::InvalidInput);
+                updated.fund_owner = value;
+            }
+            AmmConfigUpdate::CreatePoolFee(value) => updated.create_pool_fee = value,
+            AmmConfigUpdate::DisableCreatePool(value) => updated.disable_create_pool = value,
+            AmmConfigUpdate::CreatorFeeRate(value) => updated.creator_fee_rate = value,
+            AmmConfigUpdate::MaxPriceDeviationRate(value) => {
+                updated.max_price_deviation_rate = value
+            }
+            AmmConfigUpdate::PriceDeviationWindow(value) => updated.price_deviation_window = value,
+            AmmConfigUpdate::MaxPriceMoveRate(value) => updated.max_price_move_rate = value,
+            AmmConfigUpdate::PriceMoveWindow(value) => updated.price_move_window = value,
+            AmmConfigUpdate::MaxCreatorFeeRate(value) => updated.max_creator_fee_rate = value,
+            AmmConfigUpdate::LockLpAmount(value) => updated.lock_lp_amount = value,
+            AmmConfigUpdate::PriceDeviationOnWithdraw(value) => {
+                updated.price_deviation_on_withdraw = value
+            }
+            AmmConfigUpdate::LockLpRate(value) => updated.lock_lp_rate = value,
+        }
+        updated.validate()?;
+        *self = updated;
+        Ok(old_value)
+    }
+
+    /// Stores a fee rate change to apply once `effective_time` is reached, which has to be
+    /// at least `CONFIG_CHANGE_MIN_DELAY` away. An `effective_time` of 0 cancels the scheduled change.
+    pub fn schedule_change(
+        &mut self,
+        update: AmmConfigUpdate,
+        effective_time: u64,
+        block_timestamp: u64,
+    ) -> Result<(u8, u64)> {
+        let (param, value) = update.fee_rate_param().ok_or(ErrorCode::InvalidInput)?;
+        if effective_time != 0 {
+            require_gte!(
+                effective_time,
+                block_timestamp + CONFIG_CHANGE_MIN_DELAY,
+                ErrorCode::InvalidInput
+            );
+            // the change must be valid against the current config
+            self.clone().apply_update(update)?;
+        }
+        self.pending_change_param = param;
+        self.pending_change_value = value;
+        self.pending_change_effective_time = effective_time;
+        Ok((param, value))
+    }
+
+    /// Applies the scheduled change once its effective time is reached, returns the previous value
+    pub fn apply_scheduled_change(&mut self, block_timestamp: u64) -> Result<AmmConfigUpdate> {
+        let effective_time = self.pending_change_effective_time;
+        require!(
+            effective_time != 0 && block_timestamp >= effective_time,
+            ErrorCode::ConfigChangeNotReady
+        );
+        let old_value = self.apply_update(AmmConfigUpdate::from_fee_rate_param(
+            self.pending_change_param,
+            self.pending_change_value,
+        )?)?;
+        self.pending_change_param = 0;
+        self.pending_change_value = 0;
+        self.pending_change_effective_time = 0;
+        Ok(old_value)
+    }
+
+    /// The max creator fee rate of the pools
+    pub fn max_creator_fee_rate(&self) -> u64 {
+        if self.max_creator_fee_rate == 0 {
+            self.creator_fee_rate
+        } else {
+            self.max_creator_fee_rate
+        }
+    }
+
+    /// The creator fee rate charged by a pool created with `creator_fee_rate`,
+    /// capped by the max creator fee rate, 0 to inherit the config one
+    pub fn pool_creator_fee_rate(&self, creator_fee_rate: u64) -> Result<u64> {
+        require_gte!(
+            self.max_creator_fee_rate(),
+            creator_fee_rate,
+            ErrorCode::InvalidFeeRate
+        );
+        Ok(if creator_fee_rate == 0 {
+            self.creator_fee_rate
+        } else {
+            creator_fee_rate
+        })
+    }
+
+    /// The min lp amount locked when a pool is created
+    pub fn lock_lp_amount(&self) -> u64 {
+        if self.lock_lp_amount == 0 {
+            LOCK_LP_AMOUNT
+        } else {
+            self.lock_lp_amount
+        }
+    }
+
+    /// The lp amount locked when a pool is created with the initial liquidity,
+    /// so that the lock scales with the decimals of the tokens
+    pub fn lock_lp_amount_for(&self, liquidity: u64) -> u64 {
+        let share = u128::from(liquidity) * u128::from(self.lock_lp_rate)
+            / u128::from(FEE_RATE_DENOMINATOR_VALUE);
+        self.lock_lp_amount().max(share as u64)
+    }
+
+    /// The locked and the creator's lp amounts of a pool created with the initial liquidity
+    pub fn initial_lp_amounts(&self, liquidity: u64) -> Result<(u64, u64)> {
+        let lock_lp_amount = self.lock_lp_amount_for(liquidity);
+        let user_lp_amount = liquidity
+            .checked_sub(lock_lp_amount)
+            .ok_or(ErrorCode::InitLpAmountTooLess)?;
+        Ok((lock_lp_amount, user_lp_amount))
+    }
+
+    pub fn price_deviation_window(&self) -> u64 {
+        if self.price_deviation_window == 0 {
+            DEFAULT_PRICE_DEVIATION_WINDOW
+        } else {
+            self.price_deviation_window
+        }
+    }
+}
+
+/// A change of one field of `AmmConfig`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmConfigUpdate {
+    /// Raises have to be scheduled
+    TradeFeeRate(u64),
+    /// Raises have to be scheduled
+    ProtocolFeeRate(u64),
+    FundFeeRate(u64),
+    ProtocolOwner(Pubkey),
+    FundOwner(Pubkey),
+    CreatePoolFee(u64),
+    DisableCreatePool(bool),
+    /// Raises have to be scheduled
+    CreatorFeeRate(u64),
+    MaxPriceDeviationRate(u64),
+    PriceDeviationWindow(u64),
+    MaxPriceMoveRate(u64),
+    /// 0 for a single slot
+    PriceMoveWindow(u64),
+    /// 0 to cap the pool creator fee rates to `creator_fee_rate`
+    MaxCreatorFeeRate(u64),
+    /// 0 for `LOCK_LP_AMOUNT`
+    LockLpAmount(u64),
+    PriceDeviationOnWithdraw(bool),
+    /// 0 to lock `lock_lp_amount` only
+    LockLpRate(u64),
+}
+
+impl AmmConfigUpdate {
+    /// Fee rates are changed by the fee manager instead of the admin
+    pub fn is_fee_rate(&self) -> bool {
+        matches!(
+            self,
+            AmmConfigUpdate::TradeFeeRate(_)
+                | AmmConfigUpdate::ProtocolFeeRate(_)
+                | AmmConfigUpdate::FundFeeRate(_)
+                | AmmConfigUpdate::CreatorFeeRate(_)
+        )
+    }
+
+    /// The param and value stored for a scheduled change, only for the timelocked fee rates
+    pub fn fee_rate_param(&self) -> Option<(u8, u64)> {
+        match *self {
+            AmmConfigUpdate::TradeFeeRate(value) => Some((0, value)),
+            AmmConfigUpdate::ProtocolFeeRate(value) => Some((1, value)),
+            AmmConfigUpdate::CreatorFeeRate(value) => Some((7, value)),
+            _ => None,
+        }
+    }
+
+    pub fn from_fee_rate_param(param: u8, value: u64) -> Result<Self> {
+        match param {
+            0 => Ok(AmmConfigUpdate::TradeFeeRate(value)),
+            1 => Ok(AmmConfigUpdate::ProtocolFeeRate(value)),
+            7 => Ok(AmmConfigUpdate::CreatorFeeRate(value)),
+            _ => err!(ErrorCode::InvalidInput),
+        }
+    }
+
+    /// Whether applying the update raises a timelocked fee rate
+    pub fn is_timelocked_raise(&self, amm_config: &AmmConfig) -> bool {
+        match (
+            self.fee_rate_param(),
+            amm_config.current_value(self).fee_rate_param(),
+        ) {
+            (Some((_, new_value)), Some((_, old_value))) => new_value > old_value,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod amm_config_test {
+    use super::*;
+
+    #[test]
+    fn apply_update_test() {
+        let mut amm_config = AmmConfig::default();
+        let old_value = amm_config
+            .apply_update(AmmConfigUpdate::TradeFeeRate(2500))
+            .unwrap();
+        assert_eq!(old_value, AmmConfigUpdate::TradeFeeRate(0));
+        assert!(AmmConfigUpdate::TradeFeeRate(3000).is_timelocked_raise(&amm_config));
+        assert!(!AmmConfigUpdate::TradeFeeRate(2000).is_timelocked_raise(&amm_config));
+        assert!(!AmmConfigUpdate::FundFeeRate(3000).is_timelocked_raise(&amm_config));
+
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::CreatorFeeRate(
+                FEE_RATE_DENOMINATOR_VALUE - 2500
+            ))
+            .is_err());
+        amm_config
+            .apply_update(AmmConfigUpdate::ProtocolFeeRate(120_000))
+            .unwrap();
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::FundFeeRate(
+                FEE_RATE_DENOMINATOR_VALUE - 119_999
+            ))
+            .is_err());
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::ProtocolOwner(Pubkey::default()))
+            .is_err());
+        assert_eq!(
+            amm_config
+                .apply_update(AmmConfigUpdate::PriceDeviationOnWithdraw(true))
+                .unwrap(),
+            AmmConfigUpdate::PriceDeviationOnWithdraw(false)
+        );
+        assert!(amm_config.price_deviation_on_withdraw);
+
+        assert_eq!(amm_config.lock_lp_amount(), LOCK_LP_AMOUNT);
+        amm_config
+            .apply_update(AmmConfigUpdate::LockLpAmount(1_000_000))
+            .unwrap();
+        assert_eq!(amm_config.lock_lp_amount(), 1_000_000);
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::LockLpAmount(u64::MAX))
+            .is_err());
+        amm_config
+            .apply_update(AmmConfigUpdate::LockLpRate(1_000))
+            .unwrap();
+        assert_eq!(amm_config.lock_lp_amount_for(100_000_000), 1_000_000);
+        assert_eq!(amm_config.lock_lp_amount_for(10_000_000_000), 10_000_000);
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::LockLpRate(MAX_LOCK_LP_RATE + 1))
+            .is_err());
+    }
+
+    #[test]
+    fn lock_lp_amount_test() {
+        let mut amm_config = AmmConfig::default();
+        // without an amount nor a rate, the default amount is locked
+        assert_eq!(amm_config.lock_lp_amount_for(1_000), LOCK_LP_AMOUNT);
+        assert_eq!(
+            amm_config.initial_lp_amounts(1_000).unwrap(),
+            (LOCK_LP_AMOUNT, 1_000 - LOCK_LP_AMOUNT)
+        );
+        // the creator can be left without lp tokens, but the lock can't exceed the liquidity
+        assert_eq!(
+            amm_config.initial_lp_amounts(LOCK_LP_AMOUNT).unwrap(),
+            (LOCK_LP_AMOUNT, 0)
+        );
+        assert_eq!(
+            amm_config
+                .initial_lp_amounts(LOCK_LP_AMOUNT - 1)
+                .unwrap_err(),
+            ErrorCode::InitLpAmountTooLess.into()
+        );
+
+        // the share of the liquidity is locked once it exceeds the amount
+        amm_config
+            .apply_update(AmmConfigUpdate::LockLpRate(MAX_LOCK_LP_RATE))
+            .unwrap();
+        assert_eq!(amm_config.lock_lp_amount_for(5_000), LOCK_LP_AMOUNT);
+        assert_eq!(amm_config.lock_lp_amount_for(1_000_000), 10_000);
+        assert_eq!(
+            amm_config.initial_lp_amounts(1_000_000).unwrap(),
+            (10_000, 990_000)
+        );
+        assert_eq!(amm_config.lock_lp_amount_for(u64::MAX), u64::MAX / 100);
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::LockLpRate(MAX_LOCK_LP_RATE + 1))
+            .is_err());
+
+        // the max amount, a pool needs at least that much liquidity
+        amm_config
+            .apply_update(AmmConfigUpdate::LockLpAmount(MAX_LOCK_LP_AMOUNT))
+            .unwrap();
+        assert!(amm_config
+            .apply_update(AmmConfigUpdate::LockLpAmount(MAX_LOCK_LP_AMOUNT + 1))
+            .is_err());
+        assert_eq!(amm_config.lock_lp_amount_for(1_000_000), MAX_LOCK_LP_AMOUNT);
+        assert_eq!(
+            amm_config.lock_lp_amount_for(1_000_000_000_000),
+            10_000_000_000
+        );
+        assert_eq!(
+            amm_config
+                .initial_lp_amounts(MAX_LOCK_LP_AMOUNT - 1)
+                .unwrap_err(),
+            ErrorCode::InitLpAmountTooLess.into()
+        );
+        assert_eq!(
+            amm_config.initial_lp_amounts(MAX_LOCK_LP_AMOUNT).unwrap(),
+            (MAX_LOCK_LP_AMOUNT, 0)
+        );
+    }
+
+    #[test]
+    fn pool_creator_fee_rate_test() {
+        let mut amm_config = AmmConfig::default();
+        amm_config.creator_fee_rate = 500;
+        // without a max, the config rate is the max
+        assert_eq!(amm_config.max_creator_fee_rate(), 500);
+        assert_eq!(amm_config.pool_creator_fee_rate(0).unwrap(), 500);
+        assert_eq!(amm_config.pool_creator_fee_rate(300).unwrap(), 300);
+        assert_eq!(
+            amm_config.pool_creator_fee_rate(501).unwrap_err(),
+            ErrorCode::InvalidFeeRate.into()
+        );
+
+        amm_config.max_creator_fee_rate = 2000;
+        assert_eq!(amm_config.pool_creator_fee_rate(2000).unwrap(), 2000);
+        assert!(amm_config.pool_creator_fee_rate(2001).is_err());
+        assert_eq!(amm_config.pool_creator_fee_rate(0).unwrap(), 500);
+    }
+
+    #[test]
+    fn scheduled_change_test() {
+        let mut amm_config = AmmConfig::default();
+        amm_config
+            .apply_update(AmmConfigUpdate::TradeFeeRate(2500))
+            .unwrap();
+        let now = 1_700_000_000;
+
+        // a raise can't be scheduled for less than the min delay
+        assert_eq!(
+            amm_config
+                .schedule_change(
+                    AmmConfigUpdate::TradeFeeRate(10_000),
+                    now + CONFIG_CHANGE_MIN_DELAY - 1,
+                    now
+                )
+                .unwrap_err(),
+            ErrorCode::InvalidInput.into()
+        );
+        // nor to an invalid value
+        assert!(amm_config
+            .schedule_change(
+                AmmConfigUpdate::TradeFeeRate(FEE_RATE_DENOMINATOR_VALUE),
+                now + CONFIG_CHANGE_MIN_DELAY,
+                now
+            )
+            .is_err());
+        // only the timelocked fee rates are scheduled
+        assert!(amm_config
+            .schedule_change(
+                AmmConfigUpdate::FundFeeRate(10_000),
+                now + CONFIG_CHANGE_MIN_DELAY,
+                now
+            )
+            .is_err());
+
+        let effective_time = now + CONFIG_CHANGE_MIN_DELAY;
+        assert_eq!(
+            amm_config
+                .schedule_change(AmmConfigUpdate::TradeFeeRate(10_000), effective_time, now)
+                .unwrap(),
+            (0, 10_000)
+        );
+        // rejected before the delay
+        assert_eq!(
+            amm_config
+                .apply_scheduled_change(effective_time - 1)
+                .unwrap_err(),
+            ErrorCode::ConfigChangeNotReady.into()
+        );
+        assert_eq!(amm_config.trade_fee_rate, 2500);
+        // accepted after it
+        assert_eq!(
+            amm_config.apply_scheduled_change(effective_time).unwrap(),
+            AmmConfigUpdate::TradeFeeRate(2500)
+        );
+        assert_eq!(amm_config.trade_fee_rate, 10_000);
+        assert_eq!(amm_config.pending_change_effective_time, 0);
+        // and can't be applied twice
+        assert!(amm_config.apply_scheduled_change(effective_time).is_err());
+
+        // a cancelled change is never applied
+        amm_config
+            .schedule_change(
+                AmmConfigUpdate::ProtocolFeeRate(200_000),
+                effective_time,
+                now,
+            )
+            .unwrap();
+        amm_config
+            .schedule_change(AmmConfigUpdate::ProtocolFeeRate(200_000), 0, now)
+            .unwrap();
+        assert!(amm_config.apply_scheduled_change(u64::MAX).is_err());
+        assert_eq!(amm_config.protocol_fee_rate, 0);
+    }
 }
@@ -0,0 +1,111 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const CREATOR_FEE_SPLIT_SEED: &str = "creator_fee_split";
+/// Max number of recipients of a creator fee split
+pub const CREATOR_FEE_SPLIT_RECIPIENT_NUM: usize = 5;
+/// The shares of a creator fee split are in basis points
+pub const CREATOR_FEE_SPLIT_SHARE_DENOMINATOR: u16 = 10_000;
+
+/// Optional account of a pool splitting the creator fees among several recipients,
+/// anyone can distribute the fees once it's set
+#[account]
+#[derive(Default, Debug)]
+pub struct CreatorFeeSplit {
+    /// Bump to identify PDA
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    /// Number of recipients
+    pub recipient_count: u8,
+    /// Owners of the token accounts receiving the creator fees
+    pub recipients: [Pubkey; CREATOR_FEE_SPLIT_RECIPIENT_NUM],
+    /// Shares of the recipients in basis points, summing to 10000
+    pub shares: [u16; CREATOR_FEE_SPLIT_RECIPIENT_NUM],
+    /// padding
+    pub padding: [u64; 8],
+}
+
+impl CreatorFeeSplit {
+    pub const LEN: usize = 8
+        + 1
+        + 32
+        + 1
+        + 32 * CREATOR_FEE_SPLIT_RECIPIENT_NUM
+        + 2 * CREATOR_FEE_SPLIT_RECIPIENT_NUM
+        + 8 * 8;
+
+    pub fn set_recipients(&mut self, recipients: &[Pubkey], shares: &[u16]) -> Result<()> {
+        require!(
+            !recipients.is_empty()
+                && recipients.len() <= CREATOR_FEE_SPLIT_RECIPIENT_NUM
+                && recipients.len() == shares.len(),
+            ErrorCode::InvalidInput
+        );
+        require!(
+            recipients.iter().all(|key| *key != Pubkey::default()),
+            ErrorCode::InvalidInput
+        );
+        require_eq!(
+            shares.iter().map(|share| u32::from(*share)).sum::<u32>(),
+            u32::from(CREATOR_FEE_SPLIT_SHARE_DENOMINATOR),
+            ErrorCode::InvalidInput
+        );
+        self.recipient_count = recipients.len() as u8;
+        self.recipients = [Pubkey::default(); CREATOR_FEE_SPLIT_RECIPIENT_NUM];
+        self.recipients[..recipients.len()].copy_from_slice(recipients);
+        self.shares = [0u16; CREATOR_FEE_SPLIT_RECIPIENT_NUM];
+        self.shares[..shares.len()].copy_from_slice(shares);
+        Ok(())
+    }
+
+    /// Splits the amount by the shares, the rounding remainder goes to the first recipient
+    pub fn split(&self, amount: u64) -> Vec<u64> {
+        let shares = &self.shares[..self.recipient_count as usize];
+        let mut amounts: Vec<u64> = shares
+            .iter()
+            .map(|share| {
+                (u128::from(amount) * u128::from(*share)
+                    / u128::from(CREATOR_FEE_SPLIT_SHARE_DENOMINATOR)) as u64
+            })
+            .collect();
+        let remainder = amount - amounts.iter().sum::<u64>();
+        if let Some(first) = amounts.first_mut() {
+            *first += remainder;
+        }
+        amounts
+    }
+}
+
+#[cfg(test)]
+pub mod creator_fee_split_test {
+    use super::*;
+
+    #[test]
+    fn split_test() {
+        let recipients = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        let mut creator_fee_split = CreatorFeeSplit::default();
+        assert!(creator_fee_split
+            .set_recipients(&recipients, &[5000, 3000, 1000])
+            .is_err());
+        assert!(creator_fee_split
+            .set_recipients(&recipients[..2], &[5000, 3000, 2000])
+            .is_err());
+        assert!(creator_fee_split
+            .set_recipients(&[recipients[0], Pubkey::default()], &[5000, 5000])
+            .is_err());
+        creator_fee_split
+            .set_recipients(&recipients, &[5000, 3000, 2000])
+            .unwrap();
+        assert_eq!(creator_fee_split.split(1000), vec![500, 300, 200]);
+        assert_eq!(creator_fee_split.split(1001), vec![501, 300, 200]);
+        assert_eq!(
+            creator_fee_split.split(u64::MAX).iter().sum::<u64>(),
+            u64::MAX
+        );
+        assert_eq!(creator_fee_split.split(0), vec![0, 0, 0]);
+    }
+}
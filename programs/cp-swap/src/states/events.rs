@@ -1,3 +1,4 @@
+use super::{AmmConfigUpdate, PoolCreatorSettingsUpdate};
 use anchor_lang::prelude::*;
 
 /// Emitted when deposit and withdraw
@@ -43,3 +44,315 @@ pub struct SwapEvent {
     pub creator_fee: u64,
     pub creator_fee_on_input: bool,
 }
+
+/// Emitted when swap along with `SwapEvent`, carrying the full swap result and the pool after the trade
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SwapEventV2 {
+    pub pool_id: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub base_input: bool,
+    /// pool vault sub protocol, fund and creator fees, before the trade
+    pub input_vault_before: u64,
+    /// pool vault sub protocol, fund and creator fees, before the trade
+    pub output_vault_before: u64,
+    /// pool vault sub protocol, fund and creator fees, after the trade
+    pub input_vault_after: u64,
+    /// pool vault sub protocol, fund and creator fees, after the trade
+    pub output_vault_after: u64,
+    /// calculate result without transfer fee
+    pub input_amount: u64,
+    /// calculate result without transfer fee
+    pub output_amount: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    /// Amount of input tokens going to pool holders, including protocol and fund fees
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// Amount of fee tokens going to creator
+    pub creator_fee: u64,
+    pub creator_fee_on_input: bool,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    /// The creator fee rate applied, 0 if the creator fee isn't enabled
+    pub creator_fee_rate: u64,
+    pub lp_supply: u64,
+    /// The token_0 price after the trade, Q64.64
+    pub token_0_price_x64: u128,
+    /// The token_1 price after the trade, Q64.64
+    pub token_1_price_x64: u128,
+}
+
+/// Emitted when a swap trips the price movement circuit breaker of a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PriceBreakerTrippedEvent {
+    pub pool_id: Pubkey,
+    /// The slot or timestamp the window started at
+    pub window_start: u64,
+    /// The token_0 price at the start of the window, Q64.64
+    pub reference_price_x64: u128,
+    /// The token_0 price after the swap, Q64.64
+    pub price_x64: u128,
+    /// 0: ZeroForOne, 1: OneForZero
+    pub trade_direction: u8,
+}
+
+/// Emitted when a config change is scheduled, or cancelled with `effective_time` 0
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigChangeScheduledEvent {
+    pub amm_config: Pubkey,
+    /// see `AmmConfigUpdate::fee_rate_param`
+    pub param: u8,
+    pub value: u64,
+    pub effective_time: u64,
+}
+
+/// Emitted when a scheduled config change is applied
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigChangeAppliedEvent {
+    pub amm_config: Pubkey,
+    /// see `AmmConfigUpdate::fee_rate_param`
+    pub param: u8,
+    pub old_value: u64,
+    pub new_value: u64,
+}
+
+/// Emitted when a field of an amm config is updated
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub old_value: AmmConfigUpdate,
+    pub new_value: AmmConfigUpdate,
+}
+
+/// Emitted when a pool is created
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolCreatedEvent {
+    pub pool_id: Pubkey,
+    pub pool_creator: Pubkey,
+    pub amm_config: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    pub observation_key: Pubkey,
+    /// amount transferred in, including transfer fee
+    pub init_amount_0: u64,
+    /// amount transferred in, including transfer fee
+    pub init_amount_1: u64,
+    /// the lp supply, including the locked lp amount
+    pub lp_amount: u64,
+    /// the locked lp amount, never minted
+    pub lock_lp_amount: u64,
+    pub open_time: u64,
+    pub creator_fee_on: u8,
+    pub enable_creator_fee: bool,
+    /// The creator fee rate charged by the pool
+    pub creator_fee_rate: u64,
+    /// Whether created by `initialize_with_permission`
+    pub with_permission: bool,
+}
+
+/// Emitted when an amm config is created
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AmmConfigCreatedEvent {
+    pub amm_config: Pubkey,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub creator_fee_rate: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+}
+
+/// Emitted when the status of a pool is updated
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolStatusUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub authority: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
+}
+
+/// Emitted when the pool creator role is proposed, with `pending`, or accepted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolCreatorChangeEvent {
+    pub pool_id: Pubkey,
+    pub old_creator: Pubkey,
+    pub new_creator: Pubkey,
+    pub pending: bool,
+}
+
+/// Emitted when a pool is moved to another amm config, with the fees settled under the old one
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolConfigMigratedEvent {
+    pub pool_id: Pubkey,
+    pub authority: Pubkey,
+    pub old_amm_config: Pubkey,
+    pub new_amm_config: Pubkey,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    pub creator_fees_token_0: u64,
+    pub creator_fees_token_1: u64,
+}
+
+/// Emitted when the fee manager schedules the migration of a pool to an amm config with higher fees
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolConfigMigrationScheduledEvent {
+    pub pool_id: Pubkey,
+    pub new_amm_config: Pubkey,
+    pub effective_time: u64,
+}
+
+/// Emitted when the pool creator updates a pool setting
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolCreatorSettingsUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub old_value: PoolCreatorSettingsUpdate,
+    pub new_value: PoolCreatorSettingsUpdate,
+}
+
+/// Emitted when the creator fee split of a pool is set, or removed with no recipients
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CreatorFeeSplitUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub recipients: Vec<Pubkey>,
+    /// in basis points
+    pub shares: Vec<u16>,
+}
+
+/// Emitted when the status of every pool of an amm config is updated
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AmmConfigStatusUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub authority: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
+}
+
+/// Emitted when an empty pool is closed, with the dust swept to the protocol
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolClosedEvent {
+    pub pool_id: Pubkey,
+    pub authority: Pubkey,
+    pub pool_creator: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when the pool creator cancels a pool before its open time
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolCancelledEvent {
+    pub pool_id: Pubkey,
+    pub pool_creator: Pubkey,
+    /// The burned lp amount
+    pub lp_amount: u64,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when the tracked reserves of a pool absorb the tokens sent directly to the vaults
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ReservesSyncedEvent {
+    pub pool_id: Pubkey,
+    pub reserve_0_before: u64,
+    pub reserve_1_before: u64,
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+}
+
+/// Emitted when the admin recovers the tokens sent directly to the vaults of a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolSkimmedEvent {
+    pub pool_id: Pubkey,
+    pub recipient_token_0_account: Pubkey,
+    pub recipient_token_1_account: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+/// Emitted when tokens are donated to the liquidity providers of a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DonateEvent {
+    pub pool_id: Pubkey,
+    pub donor: Pubkey,
+    /// The amounts received by the vaults
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    /// The reserves after the donation
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+}
+
+/// Emitted when status bits of a pool are disabled until a timestamp
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolPausedEvent {
+    pub pool_id: Pubkey,
+    pub authority: Pubkey,
+    /// The disabled status bits
+    pub status: u8,
+    pub pause_until: u64,
+    pub reason: u8,
+}
+
+/// Emitted when protocol, fund or creator fees are collected
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CollectFeeEvent {
+    pub pool_id: Pubkey,
+    pub recipient_token_0_account: Pubkey,
+    pub recipient_token_1_account: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+    // 0: protocol, 1: fund, 2: creator
+    pub fee_type: u8,
+}
+
+/// Emitted when a permission account is created or closed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PermissionChangeEvent {
+    pub permission: Pubkey,
+    pub permission_authority: Pubkey,
+    // 0: create, 1: close
+    pub change_type: u8,
+}
+
+/// Emitted when the admin or an admin role changes, a proposed admin is pending until accepted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AdminChangeEvent {
+    pub old_account: Pubkey,
+    pub new_account: Pubkey,
+    // the changed `AdminRole`, 0: admin, 1: pending admin, 2: pause guardian, 3: fee manager
+    pub role: u8,
+}
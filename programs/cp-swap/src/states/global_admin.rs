@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+pub const GLOBAL_ADMIN_SEED: &str = "global_admin";
+
+/// The role reported by `AdminChangeEvent`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdminRole {
+    Admin,
+    PendingAdmin,
+    PauseGuardian,
+    FeeManager,
+}
+
+impl AdminRole {
+    /// Maps the `role` argument of `update_admin_role`, only the assignable roles are accepted
+    pub fn from_assignable(role: u8) -> Option<Self> {
+        match role {
+            0 => Some(AdminRole::PauseGuardian),
+            1 => Some(AdminRole::FeeManager),
+            _ => None,
+        }
+    }
+}
+
+/// Holds the current admin of the program
+#[account]
+#[derive(Default, Debug)]
+pub struct GlobalAdmin {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The admin allowed to run the admin instructions and to assign the roles
+    pub admin: Pubkey,
+    /// The proposed admin, who becomes the admin once accepting it
+    pub pending_admin: Pubkey,
+    /// Can only disable pools, a hot key for emergencies
+    pub pause_guardian: Pubkey,
+    /// Manages the fee rates of the amm configs
+    pub fee_manager: Pubkey,
+    /// padding
+    pub padding: [u64; 8],
+}
+
+impl GlobalAdmin {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 32 * 2 + 8 * 8;
+
+    pub fn can_pause(&self, key: &Pubkey) -> bool {
+        *key == self.admin || *key == self.pause_guardian
+    }
+
+    pub fn is_fee_manager(&self, key: &Pubkey) -> bool {
+        *key == self.fee_manager
+    }
+}
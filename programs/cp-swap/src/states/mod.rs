@@ -12,3 +12,15 @@ pub use oracle::*;
 
 pub mod permission;
 pub use permission::*;
+
+pub mod observation_history;
+pub use observation_history::*;
+
+pub mod global_admin;
+pub use global_admin::*;
+
+pub mod creator_fee_split;
+pub use creator_fee_split::*;
+
+pub mod pool_config_migration;
+pub use pool_config_migration::*;
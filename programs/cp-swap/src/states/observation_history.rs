@@ -0,0 +1,281 @@
+use super::{average_price_x64, ObservationStateV2};
+use crate::error::ErrorCode;
+use crate::utils::{AccountLoad, U256};
+/// Downsampled long-horizon checkpoints of the pool oracle
+///
+use anchor_lang::prelude::*;
+
+// Number of ObservationHistoryState checkpoints, 8 days of hourly checkpoints
+pub const OBSERVATION_HISTORY_NUM: usize = 192;
+pub const OBSERVATION_HISTORY_DURATION: u64 = 3600;
+
+/// The element of checkpoints in ObservationHistoryState
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct HistoryCheckpoint {
+    /// The block timestamp of the checkpoint
+    pub block_timestamp: u64,
+    /// the cumulative of token0 price at the block timestamp, Q64.64, stored as the words of a U256
+    pub cumulative_token_0_price_x64: [u64; 4],
+    /// the cumulative of token1 price at the block timestamp, Q64.64, stored as the words of a U256
+    pub cumulative_token_1_price_x64: [u64; 4],
+    /// the cumulative amount of token0 swapped at the block timestamp
+    pub cumulative_token_0_volume: u128,
+    /// the cumulative amount of token1 swapped at the block timestamp
+    pub cumulative_token_1_volume: u128,
+    /// the cumulative of liquidity at the block timestamp
+    pub cumulative_liquidity: u128,
+}
+impl HistoryCheckpoint {
+    pub const LEN: usize = 8 + 32 + 32 + 16 * 3;
+}
+
+/// Optional account keeping an hourly snapshot of the accumulators of a pool's
+/// `ObservationStateV2`, so averages can be taken over days instead of minutes.
+/// It's larger than the accounts a program can create, so it's allocated by the client.
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ObservationHistoryState {
+    /// Whether the ObservationHistoryState has a checkpoint
+    pub initialized: bool,
+    /// the most-recently written index of the checkpoints array
+    pub checkpoint_index: u16,
+    pub pool_id: Pubkey,
+    /// checkpoint array
+    pub checkpoints: [HistoryCheckpoint; OBSERVATION_HISTORY_NUM],
+    /// padding for feature update
+    pub padding: [u64; 4],
+}
+
+impl Default for ObservationHistoryState {
+    #[inline]
+    fn default() -> ObservationHistoryState {
+        ObservationHistoryState {
+            initialized: false,
+            checkpoint_index: 0,
+            pool_id: Pubkey::default(),
+            checkpoints: [HistoryCheckpoint::default(); OBSERVATION_HISTORY_NUM],
+            padding: [0u64; 4],
+        }
+    }
+}
+
+/// Averages observed over a window by `ObservationHistoryState::observe`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryObservation {
+    /// The actual number of seconds averaged over, at least the requested window
+    pub duration: u64,
+    pub token_0_price_x64: u128,
+    pub token_1_price_x64: u128,
+    /// The amount of token0 swapped during the duration
+    pub token_0_volume: u128,
+    /// The amount of token1 swapped during the duration
+    pub token_1_volume: u128,
+    pub liquidity: u128,
+}
+
+impl ObservationHistoryState {
+    pub const LEN: usize =
+        8 + 1 + 2 + 32 + (HistoryCheckpoint::LEN * OBSERVATION_HISTORY_NUM) + 8 * 4;
+
+    /// Writes a checkpoint of the observation state accumulators when at least
+    /// `OBSERVATION_HISTORY_DURATION` seconds passed since the last one.
+    /// The accumulators are kept by the observation state on every swap, so a swap
+    /// not passing the history account only delays the next checkpoint.
+    pub fn update(&mut self, observation_state: &ObservationStateV2) {
+        if !observation_state.initialized {
+            return;
+        }
+        let block_timestamp = observation_state.last_update_timestamp;
+        let checkpoint_index = self.checkpoint_index as usize;
+        let next_checkpoint_index = if self.initialized {
+            let time_since_last_checkpoint =
+                block_timestamp.saturating_sub(self.checkpoints[checkpoint_index].block_timestamp);
+            if time_since_last_checkpoint < OBSERVATION_HISTORY_DURATION {
+                return;
+            }
+            (checkpoint_index + 1) % OBSERVATION_HISTORY_NUM
+        } else {
+            self.initialized = true;
+            checkpoint_index
+        };
+        self.checkpoints[next_checkpoint_index] = HistoryCheckpoint {
+            block_timestamp,
+            cumulative_token_0_price_x64: observation_state.cumulative_token_0_price_x64,
+            cumulative_token_1_price_x64: observation_state.cumulative_token_1_price_x64,
+            cumulative_token_0_volume: observation_state.cumulative_token_0_volume,
+            cumulative_token_1_volume: observation_state.cumulative_token_1_volume,
+            cumulative_liquidity: observation_state.cumulative_liquidity,
+        };
+        self.checkpoint_index = next_checkpoint_index as u16;
+    }
+
+    /// Returns the averages from the newest checkpoint at least `window` seconds old
+    /// until now. The current prices and liquidity are used since the last oracle update.
+    ///
+    /// # Arguments
+    ///
+    /// * `observation_state` - The pool's observation state, providing the latest accumulators
+    /// * `block_timestamp` - The current timestamp
+    /// * `window` - The minimum number of seconds to average over
+    /// * `token_0_price_x64` - The current token_0 price
+    /// * `token_1_price_x64` - The current token_1 price
+    /// * `liquidity` - The current liquidity
+    ///
+    pub fn observe(
+        &self,
+        observation_state: &ObservationStateV2,
+        block_timestamp: u64,
+        window: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+        liquidity: u64,
+    ) -> Result<HistoryObservation> {
+        require!(window > 0, ErrorCode::InvalidInput);
+        require!(
+            self.initialized && observation_state.initialized,
+            ErrorCode::InsufficientObservation
+        );
+        let elapsed = block_timestamp.saturating_sub(observation_state.last_update_timestamp);
+        let cumulative_token_0_price_x64 = U256(observation_state.cumulative_token_0_price_x64)
+            .overflowing_add(U256::from(token_0_price_x64) * U256::from(elapsed))
+            .0;
+        let cumulative_token_1_price_x64 = U256(observation_state.cumulative_token_1_price_x64)
+            .overflowing_add(U256::from(token_1_price_x64) * U256::from(elapsed))
+            .0;
+        let cumulative_liquidity = observation_state
+            .cumulative_liquidity
+            .wrapping_add(u128::from(liquidity) * u128::from(elapsed));
+
+        let target_timestamp = block_timestamp.saturating_sub(window);
+        let checkpoint = (0..OBSERVATION_HISTORY_NUM)
+            .map(|i| {
+                self.checkpoints[(self.checkpoint_index as usize + OBSERVATION_HISTORY_NUM - i)
+                    % OBSERVATION_HISTORY_NUM]
+            })
+            .take_while(|checkpoint| checkpoint.block_timestamp != 0)
+            .find(|checkpoint| checkpoint.block_timestamp <= target_timestamp)
+            .ok_or(ErrorCode::InsufficientObservation)?;
+        let duration = block_timestamp - checkpoint.block_timestamp;
+        Ok(HistoryObservation {
+            duration,
+            token_0_price_x64: average_price_x64(
+                cumulative_token_0_price_x64,
+                U256(checkpoint.cumulative_token_0_price_x64),
+                duration,
+            )?,
+            token_1_price_x64: average_price_x64(
+                cumulative_token_1_price_x64,
+                U256(checkpoint.cumulative_token_1_price_x64),
+                duration,
+            )?,
+            token_0_volume: observation_state
+                .cumulative_token_0_volume
+                .wrapping_sub(checkpoint.cumulative_token_0_volume),
+            token_1_volume: observation_state
+                .cumulative_token_1_volume
+                .wrapping_sub(checkpoint.cumulative_token_1_volume),
+            liquidity: cumulative_liquidity.wrapping_sub(checkpoint.cumulative_liquidity)
+                / u128::from(duration),
+        })
+    }
+}
+
+/// Loads the pool's history account from the first remaining account.
+/// Once a pool has a history account, every oracle update has to pass it so no checkpoint is missed.
+pub fn load_observation_history<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    observation_history_key: Pubkey,
+) -> Result<Option<AccountLoad<'info, ObservationHistoryState>>> {
+    if observation_history_key == Pubkey::default() {
+        return Ok(None);
+    }
+    let account_info = remaining_accounts.first().ok_or(ErrorCode::InvalidInput)?;
+    require_keys_eq!(
+        account_info.key(),
+        observation_history_key,
+        ErrorCode::InvalidInput
+    );
+    Ok(Some(AccountLoad::try_from(account_info)?))
+}
+
+#[cfg(test)]
+pub mod observation_history_test {
+    use super::*;
+
+    #[test]
+    fn observation_history_state_size_test() {
+        assert_eq!(
+            std::mem::size_of::<ObservationHistoryState>(),
+            ObservationHistoryState::LEN - 8
+        )
+    }
+
+    #[test]
+    fn observation_history_observe_test() {
+        let mut observation_state = ObservationStateV2::default();
+        let mut observation_history = ObservationHistoryState::default();
+        let start = 1_700_000_000u64;
+        let price_x64 = 1u128 << 64;
+        observation_state
+            .update(start, price_x64, price_x64, 0, 0, 0)
+            .unwrap();
+        observation_history.update(&observation_state);
+        // price 1 for a day, then price 3 for a day, one swap every 10 minutes
+        for t in (600..=2 * 86400).step_by(600) {
+            let price = if t <= 86400 { price_x64 } else { 3 * price_x64 };
+            observation_state
+                .update(start + t, price, price, 10, 20, 1000)
+                .unwrap();
+            observation_history.update(&observation_state);
+        }
+        let checkpoint_index = observation_history.checkpoint_index;
+        assert_eq!(checkpoint_index, 48);
+
+        let observation = observation_history
+            .observe(
+                &observation_state,
+                start + 2 * 86400,
+                2 * 86400,
+                3 * price_x64,
+                3 * price_x64,
+                1000,
+            )
+            .unwrap();
+        assert_eq!(
+            observation,
+            HistoryObservation {
+                duration: 2 * 86400,
+                token_0_price_x64: 2 * price_x64,
+                token_1_price_x64: 2 * price_x64,
+                token_0_volume: 10 * 288,
+                token_1_volume: 20 * 288,
+                liquidity: 1000,
+            }
+        );
+        // the window is rounded to the previous checkpoint
+        let observation = observation_history
+            .observe(
+                &observation_state,
+                start + 2 * 86400 + 1800,
+                86400,
+                3 * price_x64,
+                3 * price_x64,
+                1000,
+            )
+            .unwrap();
+        assert_eq!(observation.duration, 86400 + 1800);
+        assert!(observation_history
+            .observe(
+                &observation_state,
+                start + 2 * 86400,
+                2 * 86400 + 1,
+                price_x64,
+                price_x64,
+                1000,
+            )
+            .is_err());
+    }
+}
@@ -1,4 +1,7 @@
+use super::ObservationHistoryState;
+use crate::curve::fees::FEE_RATE_DENOMINATOR_VALUE;
 use crate::error::ErrorCode;
+use crate::utils::{AccountLoad, U256};
 /// Oracle provides price data useful for a wide variety of system designs
 ///
 use anchor_lang::prelude::*;
@@ -141,12 +144,448 @@ impl ObservationState {
     }
 }
 
+impl ObservationState {
+    /// Returns the Q64.64 time-weighted average prices over the last `window` seconds.
+    /// The cumulative of a finished observation is updated in place for up to
+    /// `OBSERVATION_UPDATE_DURATION_DEFAULT` seconds after its `block_timestamp`,
+    /// so the result is only accurate to within that duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_timestamp` - The current timestamp
+    /// * `window` - The number of seconds to average over
+    /// * `token_0_price_x64` - The current token_0 price, used since the last update
+    /// * `token_1_price_x64` - The current token_1 price, used since the last update
+    ///
+    pub fn twap_price_x64(
+        &self,
+        block_timestamp: u64,
+        window: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+    ) -> Result<(u128, u128)> {
+        require!(self.initialized, ErrorCode::InsufficientObservation);
+        let latest = self.observations[self.observation_index as usize];
+        let last_update_timestamp = if self.last_update_timestamp == 0 {
+            latest.block_timestamp
+        } else {
+            self.last_update_timestamp
+        };
+        let elapsed = block_timestamp.saturating_sub(last_update_timestamp);
+        let cumulative_token_0_price_x32 = latest.cumulative_token_0_price_x32.wrapping_add(
+            (token_0_price_x64 >> 32)
+                .checked_mul(elapsed.into())
+                .ok_or(ErrorCode::MathOverflow)?,
+        );
+        let cumulative_token_1_price_x32 = latest.cumulative_token_1_price_x32.wrapping_add(
+            (token_1_price_x64 >> 32)
+                .checked_mul(elapsed.into())
+                .ok_or(ErrorCode::MathOverflow)?,
+        );
+
+        let target_timestamp = block_timestamp.saturating_sub(window);
+        let (start_timestamp, start_token_0_price_x32, start_token_1_price_x32) =
+            if last_update_timestamp <= target_timestamp {
+                (
+                    last_update_timestamp,
+                    latest.cumulative_token_0_price_x32,
+                    latest.cumulative_token_1_price_x32,
+                )
+            } else {
+                // the latest observation is still being accumulated, start from the finished ones
+                let observation = (1..OBSERVATION_NUM)
+                    .map(|i| {
+                        self.observations[(self.observation_index as usize + OBSERVATION_NUM - i)
+                            % OBSERVATION_NUM]
+                    })
+                    .take_while(|observation| observation.block_timestamp != 0)
+                    .find(|observation| observation.block_timestamp <= target_timestamp)
+                    .ok_or(ErrorCode::InsufficientObservation)?;
+                (
+                    observation.block_timestamp,
+                    observation.cumulative_token_0_price_x32,
+                    observation.cumulative_token_1_price_x32,
+                )
+            };
+        let duration = block_timestamp.saturating_sub(start_timestamp);
+        if duration == 0 {
+            return Ok((token_0_price_x64, token_1_price_x64));
+        }
+        Ok((
+            (cumulative_token_0_price_x32.wrapping_sub(start_token_0_price_x32)
+                / u128::from(duration))
+            .checked_shl(32)
+            .ok_or(ErrorCode::MathOverflow)?,
+            (cumulative_token_1_price_x32.wrapping_sub(start_token_1_price_x32)
+                / u128::from(duration))
+            .checked_shl(32)
+            .ok_or(ErrorCode::MathOverflow)?,
+        ))
+    }
+}
+
+/// The element of observations in ObservationStateV2
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct ObservationV2 {
+    /// The block timestamp of the observation
+    pub block_timestamp: u64,
+    /// the cumulative of token0 price at the block timestamp, Q64.64, stored as the words of a U256
+    pub cumulative_token_0_price_x64: [u64; 4],
+    /// the cumulative of token1 price at the block timestamp, Q64.64, stored as the words of a U256
+    pub cumulative_token_1_price_x64: [u64; 4],
+}
+impl ObservationV2 {
+    pub const LEN: usize = 8 + 32 + 32;
+}
+
+/// Oracle account of the pools created with Q64.64 accumulators
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ObservationStateV2 {
+    /// Whether the ObservationStateV2 is initialized
+    pub initialized: bool,
+    /// the most-recently written index of the observations array
+    pub observation_index: u16,
+    pub pool_id: Pubkey,
+    /// observation array
+    pub observations: [ObservationV2; OBSERVATION_NUM],
+    /// the last update timestamp
+    pub last_update_timestamp: u64,
+    /// the cumulative of token0 price at the last update timestamp, Q64.64
+    pub cumulative_token_0_price_x64: [u64; 4],
+    /// the cumulative of token1 price at the last update timestamp, Q64.64
+    pub cumulative_token_1_price_x64: [u64; 4],
+    /// the cumulative amount of token0 swapped in or out of the pool
+    pub cumulative_token_0_volume: u128,
+    /// the cumulative amount of token1 swapped in or out of the pool
+    pub cumulative_token_1_volume: u128,
+    /// the cumulative of liquidity, sqrt(token_0_amount * token_1_amount), during the duration time
+    pub cumulative_liquidity: u128,
+    /// padding for feature update
+    pub padding: [u64; 2],
+}
+
+impl Default for ObservationStateV2 {
+    #[inline]
+    fn default() -> ObservationStateV2 {
+        ObservationStateV2 {
+            initialized: false,
+            observation_index: 0,
+            pool_id: Pubkey::default(),
+            observations: [ObservationV2::default(); OBSERVATION_NUM],
+            last_update_timestamp: 0,
+            cumulative_token_0_price_x64: [0u64; 4],
+            cumulative_token_1_price_x64: [0u64; 4],
+            cumulative_token_0_volume: 0,
+            cumulative_token_1_volume: 0,
+            cumulative_liquidity: 0,
+            padding: [0u64; 2],
+        }
+    }
+}
+
+impl ObservationStateV2 {
+    pub const LEN: usize =
+        8 + 1 + 2 + 32 + (ObservationV2::LEN * OBSERVATION_NUM) + 8 + 32 * 2 + 16 * 3 + 8 * 2;
+
+    /// Accumulates the prices since the last update, and writes a new observation
+    /// when at least `OBSERVATION_UPDATE_DURATION_DEFAULT` seconds passed since the last one.
+    /// Unlike `ObservationState`, a written observation is never modified afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The ObservationStateV2 account to write in
+    /// * `block_timestamp` - The current timestamp of to update
+    /// * `token_0_price_x64` - The token_0 price held since the last update
+    /// * `token_1_price_x64` - The token_1 price held since the last update
+    /// * `token_0_volume` - The amount of token_0 swapped in or out by the current swap
+    /// * `token_1_volume` - The amount of token_1 swapped in or out by the current swap
+    /// * `liquidity` - The liquidity held since the last update
+    ///
+    pub fn update(
+        &mut self,
+        block_timestamp: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+        token_0_volume: u64,
+        token_1_volume: u64,
+        liquidity: u64,
+    ) -> Result<()> {
+        self.cumulative_token_0_volume = self
+            .cumulative_token_0_volume
+            .wrapping_add(token_0_volume.into());
+        self.cumulative_token_1_volume = self
+            .cumulative_token_1_volume
+            .wrapping_add(token_1_volume.into());
+        let observation_index = self.observation_index as usize;
+        if !self.initialized {
+            // skip the pool init price
+            self.initialized = true;
+            self.observations[observation_index] = ObservationV2 {
+                block_timestamp,
+                ..Default::default()
+            };
+            self.last_update_timestamp = block_timestamp;
+            self.cumulative_token_0_price_x64 = [0u64; 4];
+            self.cumulative_token_1_price_x64 = [0u64; 4];
+            self.cumulative_liquidity = 0;
+            return Ok(());
+        }
+        let time_since_last_update = block_timestamp.saturating_sub(self.last_update_timestamp);
+        if time_since_last_update == 0 {
+            return Ok(());
+        }
+        // a Q64.64 price fits in 128 bits, so the product can't overflow 256 bits
+        let cumulative_token_0_price_x64 = U256(self.cumulative_token_0_price_x64)
+            .overflowing_add(U256::from(token_0_price_x64) * U256::from(time_since_last_update))
+            .0;
+        let cumulative_token_1_price_x64 = U256(self.cumulative_token_1_price_x64)
+            .overflowing_add(U256::from(token_1_price_x64) * U256::from(time_since_last_update))
+            .0;
+        self.cumulative_token_0_price_x64 = cumulative_token_0_price_x64.0;
+        self.cumulative_token_1_price_x64 = cumulative_token_1_price_x64.0;
+        self.cumulative_liquidity = self
+            .cumulative_liquidity
+            .wrapping_add(u128::from(liquidity) * u128::from(time_since_last_update));
+        self.last_update_timestamp = block_timestamp;
+
+        let time_since_last_observation =
+            block_timestamp.saturating_sub(self.observations[observation_index].block_timestamp);
+        if time_since_last_observation >= OBSERVATION_UPDATE_DURATION_DEFAULT {
+            let next_observation_index = (observation_index + 1) % OBSERVATION_NUM;
+            self.observations[next_observation_index] = ObservationV2 {
+                block_timestamp,
+                cumulative_token_0_price_x64: cumulative_token_0_price_x64.0,
+                cumulative_token_1_price_x64: cumulative_token_1_price_x64.0,
+            };
+            self.observation_index = next_observation_index as u16;
+        }
+        Ok(())
+    }
+
+    /// Returns the Q64.64 time-weighted average prices over the last `window` seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_timestamp` - The current timestamp
+    /// * `window` - The number of seconds to average over
+    /// * `token_0_price_x64` - The current token_0 price, used since the last update
+    /// * `token_1_price_x64` - The current token_1 price, used since the last update
+    ///
+    pub fn twap_price_x64(
+        &self,
+        block_timestamp: u64,
+        window: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+    ) -> Result<(u128, u128)> {
+        require!(self.initialized, ErrorCode::InsufficientObservation);
+        let elapsed = U256::from(block_timestamp.saturating_sub(self.last_update_timestamp));
+        let cumulative_token_0_price_x64 = U256(self.cumulative_token_0_price_x64)
+            .overflowing_add(U256::from(token_0_price_x64) * elapsed)
+            .0;
+        let cumulative_token_1_price_x64 = U256(self.cumulative_token_1_price_x64)
+            .overflowing_add(U256::from(token_1_price_x64) * elapsed)
+            .0;
+
+        let target_timestamp = block_timestamp.saturating_sub(window);
+        let (start_timestamp, start_token_0_price_x64, start_token_1_price_x64) =
+            if self.last_update_timestamp <= target_timestamp {
+                (
+                    self.last_update_timestamp,
+                    self.cumulative_token_0_price_x64,
+                    self.cumulative_token_1_price_x64,
+                )
+            } else {
+                let observation = (0..OBSERVATION_NUM)
+                    .map(|i| {
+                        self.observations[(self.observation_index as usize + OBSERVATION_NUM - i)
+                            % OBSERVATION_NUM]
+                    })
+                    .take_while(|observation| observation.block_timestamp != 0)
+                    .find(|observation| observation.block_timestamp <= target_timestamp)
+                    .ok_or(ErrorCode::InsufficientObservation)?;
+                (
+                    observation.block_timestamp,
+                    observation.cumulative_token_0_price_x64,
+                    observation.cumulative_token_1_price_x64,
+                )
+            };
+        let duration = block_timestamp.saturating_sub(start_timestamp);
+        if duration == 0 {
+            return Ok((token_0_price_x64, token_1_price_x64));
+        }
+        Ok((
+            average_price_x64(
+                cumulative_token_0_price_x64,
+                U256(start_token_0_price_x64),
+                duration,
+            )?,
+            average_price_x64(
+                cumulative_token_1_price_x64,
+                U256(start_token_1_price_x64),
+                duration,
+            )?,
+        ))
+    }
+}
+
+pub(crate) fn average_price_x64(
+    cumulative_end: U256,
+    cumulative_start: U256,
+    duration: u64,
+) -> Result<u128> {
+    let average = cumulative_end.overflowing_sub(cumulative_start).0 / U256::from(duration);
+    if average > U256::from(u128::MAX) {
+        return err!(ErrorCode::MathOverflow);
+    }
+    Ok(average.as_u128())
+}
+
+/// The observation account of a pool, pools created before `ObservationStateV2`
+/// keep the Q32 `ObservationState`.
+pub enum PoolObservation<'info> {
+    V1(AccountLoad<'info, ObservationState>),
+    V2(AccountLoad<'info, ObservationStateV2>),
+}
+
+impl<'info> PoolObservation<'info> {
+    pub fn try_from(account_info: &AccountInfo<'info>) -> Result<Self> {
+        let is_v2 = account_info
+            .try_borrow_data()?
+            .starts_with(ObservationStateV2::DISCRIMINATOR);
+        if is_v2 {
+            Ok(Self::V2(AccountLoad::try_from(account_info)?))
+        } else {
+            Ok(Self::V1(AccountLoad::try_from(account_info)?))
+        }
+    }
+
+    /// Writes the Q64.64 prices held since the last update, truncated to Q32 for legacy accounts.
+    /// Volume and liquidity are only tracked by `ObservationStateV2`, which also feeds
+    /// the hourly checkpoints of the pool's `ObservationHistoryState` when it is given.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &self,
+        block_timestamp: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+        token_0_volume: u64,
+        token_1_volume: u64,
+        liquidity: u64,
+        observation_history: Option<&AccountLoad<'info, ObservationHistoryState>>,
+    ) -> Result<()> {
+        match self {
+            Self::V1(loader) => loader.load_mut()?.update(
+                block_timestamp,
+                token_0_price_x64 >> 32,
+                token_1_price_x64 >> 32,
+            ),
+            Self::V2(loader) => {
+                let mut observation_state = loader.load_mut()?;
+                observation_state.update(
+                    block_timestamp,
+                    token_0_price_x64,
+                    token_1_price_x64,
+                    token_0_volume,
+                    token_1_volume,
+                    liquidity,
+                )?;
+                if let Some(observation_history) = observation_history {
+                    observation_history.load_mut()?.update(&observation_state);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks the token_0 spot price is within `max_deviation_rate` of its TWAP over
+    /// the last `window` seconds, see `check_twap_deviation`.
+    pub fn check_price_deviation(
+        &self,
+        block_timestamp: u64,
+        window: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+        max_deviation_rate: u64,
+    ) -> Result<()> {
+        if max_deviation_rate == 0 {
+            return Ok(());
+        }
+        check_twap_deviation(
+            self.twap_price_x64(
+                block_timestamp,
+                window,
+                token_0_price_x64,
+                token_1_price_x64,
+            ),
+            token_0_price_x64,
+            max_deviation_rate,
+        )
+    }
+
+    /// Returns the Q64.64 time-weighted average prices over the last `window` seconds
+    pub fn twap_price_x64(
+        &self,
+        block_timestamp: u64,
+        window: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+    ) -> Result<(u128, u128)> {
+        match self {
+            Self::V1(loader) => loader.load()?.twap_price_x64(
+                block_timestamp,
+                window,
+                token_0_price_x64,
+                token_1_price_x64,
+            ),
+            Self::V2(loader) => loader.load()?.twap_price_x64(
+                block_timestamp,
+                window,
+                token_0_price_x64,
+                token_1_price_x64,
+            ),
+        }
+    }
+}
+
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
 ///
 pub fn block_timestamp() -> u64 {
     Clock::get().unwrap().unix_timestamp as u64 // truncation is desired
 }
 
+/// Checks the token_0 spot price is within `max_deviation_rate` of the TWAP returned by `twap_price_x64`.
+/// Pools without any swap yet, or whose oracle doesn't reach back `window` seconds, have no TWAP
+/// to compare against and pass: the guard starts once the oracle history covers the window.
+pub fn check_twap_deviation(
+    twap_price_x64: Result<(u128, u128)>,
+    token_0_price_x64: u128,
+    max_deviation_rate: u64,
+) -> Result<()> {
+    let twap_token_0_price_x64 = match twap_price_x64 {
+        Ok((twap_token_0_price_x64, _)) => twap_token_0_price_x64,
+        Err(e) if e == ErrorCode::InsufficientObservation.into() => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    let deviation = U256::from(token_0_price_x64.abs_diff(twap_token_0_price_x64))
+        * U256::from(FEE_RATE_DENOMINATOR_VALUE);
+    #[cfg(feature = "enable-log")]
+    msg!(
+        "spot_price_x64:{}, twap_price_x64:{}",
+        token_0_price_x64,
+        twap_token_0_price_x64
+    );
+    require!(
+        deviation <= U256::from(twap_token_0_price_x64) * U256::from(max_deviation_rate),
+        ErrorCode::PriceDeviationExceeded
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 pub fn block_timestamp_mock() -> u64 {
     SystemTime::now()
@@ -166,4 +605,135 @@ pub mod observation_test {
             ObservationState::LEN - 8
         )
     }
+
+    #[test]
+    fn observation_state_v2_size_test() {
+        assert_eq!(
+            std::mem::size_of::<ObservationStateV2>(),
+            ObservationStateV2::LEN - 8
+        )
+    }
+
+    #[test]
+    fn observation_v2_twap_test() {
+        let mut observation_state = ObservationStateV2::default();
+        let start = block_timestamp_mock();
+        let price_x64 = 1u128 << 64;
+        observation_state
+            .update(start, price_x64, price_x64, 0, 0, 0)
+            .unwrap();
+        // price 1 for 100 seconds, then price 3 for 100 seconds
+        for t in (10..=100).step_by(10) {
+            observation_state
+                .update(start + t, price_x64, price_x64, 1, 2, 100)
+                .unwrap();
+        }
+        for t in (110..=200).step_by(10) {
+            observation_state
+                .update(start + t, 3 * price_x64, 3 * price_x64, 1, 2, 100)
+                .unwrap();
+        }
+        let (twap_0, twap_1) = observation_state
+            .twap_price_x64(start + 200, 200, 3 * price_x64, 3 * price_x64)
+            .unwrap();
+        assert_eq!(twap_0, 2 * price_x64);
+        assert_eq!(twap_1, 2 * price_x64);
+        // the current price is used since the last update
+        let (twap_0, _) = observation_state
+            .twap_price_x64(start + 300, 200, price_x64, price_x64)
+            .unwrap();
+        assert_eq!(twap_0, 2 * price_x64);
+        assert!(observation_state
+            .twap_price_x64(start + 200, 201, price_x64, price_x64)
+            .is_err());
+        let cumulative_token_0_volume = observation_state.cumulative_token_0_volume;
+        let cumulative_token_1_volume = observation_state.cumulative_token_1_volume;
+        let cumulative_liquidity = observation_state.cumulative_liquidity;
+        assert_eq!(cumulative_token_0_volume, 20);
+        assert_eq!(cumulative_token_1_volume, 40);
+        assert_eq!(cumulative_liquidity, 100 * 200);
+
+        // prices far below 2^-32 keep their precision
+        let mut observation_state = ObservationStateV2::default();
+        observation_state.update(start, 3, 5, 0, 0, 0).unwrap();
+        observation_state.update(start + 20, 3, 5, 0, 0, 0).unwrap();
+        let (twap_0, twap_1) = observation_state
+            .twap_price_x64(start + 20, 20, 3, 5)
+            .unwrap();
+        assert_eq!(twap_0, 3);
+        assert_eq!(twap_1, 5);
+    }
+
+    #[test]
+    fn price_deviation_test() {
+        let start = block_timestamp_mock();
+        let price_x64 = 1u128 << 64;
+        let max_deviation_rate = 10_000; // 1%
+
+        // no swap yet, the guard waits for the oracle to cover the window
+        let observation_state = ObservationStateV2::default();
+        check_twap_deviation(
+            observation_state.twap_price_x64(start, 100, 2 * price_x64, price_x64 / 2),
+            2 * price_x64,
+            max_deviation_rate,
+        )
+        .unwrap();
+
+        let mut observation_state = ObservationStateV2::default();
+        observation_state
+            .update(start, price_x64, price_x64, 0, 0, 0)
+            .unwrap();
+        observation_state
+            .update(start + 50, price_x64, price_x64, 0, 0, 0)
+            .unwrap();
+        // the oracle only reaches back 50 seconds of the 100 second window
+        check_twap_deviation(
+            observation_state.twap_price_x64(start + 50, 100, 2 * price_x64, price_x64 / 2),
+            2 * price_x64,
+            max_deviation_rate,
+        )
+        .unwrap();
+
+        // once it covers the window, a spot price moved away from the TWAP is rejected
+        observation_state
+            .update(start + 100, price_x64, price_x64, 0, 0, 0)
+            .unwrap();
+        let spot_price_x64 = price_x64 * 102 / 100;
+        assert_eq!(
+            check_twap_deviation(
+                observation_state.twap_price_x64(start + 100, 100, spot_price_x64, price_x64),
+                spot_price_x64,
+                max_deviation_rate,
+            )
+            .unwrap_err(),
+            ErrorCode::PriceDeviationExceeded.into()
+        );
+        let spot_price_x64 = price_x64 * 1005 / 1000;
+        check_twap_deviation(
+            observation_state.twap_price_x64(start + 100, 100, spot_price_x64, price_x64),
+            spot_price_x64,
+            max_deviation_rate,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn observation_v1_twap_test() {
+        let mut observation_state = ObservationState::default();
+        let start = block_timestamp_mock();
+        let price_x32 = 1u128 << 32;
+        observation_state
+            .update(start, price_x32, price_x32)
+            .unwrap();
+        for t in (15..=150).step_by(15) {
+            observation_state
+                .update(start + t, 2 * price_x32, 2 * price_x32)
+                .unwrap();
+        }
+        let (twap_0, twap_1) = observation_state
+            .twap_price_x64(start + 150, 150, 2 << 64, 2 << 64)
+            .unwrap();
+        assert_eq!(twap_0, 2 << 64);
+        assert_eq!(twap_1, 2 << 64);
+    }
 }
@@ -1,6 +1,10 @@
+use super::CreatorFeeOn;
+use crate::error::ErrorCode;
 use anchor_lang::prelude::*;
 
 pub const PERMISSION_SEED: &str = "permission";
+/// Max number of amm configs a permission can be scoped to
+pub const PERMISSION_AMM_CONFIG_NUM: usize = 4;
 
 /// Holds the current owner of the factory
 #[account]
@@ -8,10 +12,104 @@ pub const PERMISSION_SEED: &str = "permission";
 pub struct Permission {
     /// authority
     pub authority: Pubkey,
+    /// The amm configs pools can be created with, any config if all default
+    pub allowed_amm_configs: [Pubkey; PERMISSION_AMM_CONFIG_NUM],
+    /// The timestamp the permission expires at, 0 if it doesn't expire
+    pub expire_time: u64,
+    /// Max number of pools to be created, 0 if unlimited
+    pub max_pool_count: u64,
+    /// Number of pools created
+    pub pool_count: u64,
+    /// Max creator fee rate of the pools to be created, denominated in hundredths of a bip (10^-6),
+    /// 0 if unlimited
+    pub max_creator_fee_rate: u64,
+    /// Bitwise representation of the allowed `CreatorFeeOn` modes, all modes if 0
+    /// bit0: BothToken, bit1: OnlyToken0, bit2: OnlyToken1
+    pub allowed_creator_fee_on: u8,
+    pub padding1: [u8; 7],
     /// padding
-    pub padding: [u64; 30],
+    pub padding: [u64; 9],
 }
 
 impl Permission {
-    pub const LEN: usize = 8 + 32 + 8 * 30;
+    pub const LEN: usize = 8 + 32 + 32 * PERMISSION_AMM_CONFIG_NUM + 8 * 4 + 1 + 7 + 8 * 9;
+
+    /// Checks a pool can be created with the config, fee mode and creator fee rate,
+    /// and counts it against the quota
+    pub fn use_for_pool(
+        &mut self,
+        amm_config: Pubkey,
+        creator_fee_on: &CreatorFeeOn,
+        creator_fee_rate: u64,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        require!(
+            self.expire_time == 0 || block_timestamp < self.expire_time,
+            ErrorCode::PermissionDenied
+        );
+        require!(
+            self.allowed_amm_configs
+                .iter()
+                .all(|key| *key == Pubkey::default())
+                || self.allowed_amm_configs.contains(&amm_config),
+            ErrorCode::PermissionDenied
+        );
+        require!(
+            self.allowed_creator_fee_on == 0
+                || self.allowed_creator_fee_on & (1 << creator_fee_on.to_u8()) != 0,
+            ErrorCode::PermissionDenied
+        );
+        require!(
+            self.max_creator_fee_rate == 0 || creator_fee_rate <= self.max_creator_fee_rate,
+            ErrorCode::PermissionDenied
+        );
+        require!(
+            self.max_pool_count == 0 || self.pool_count < self.max_pool_count,
+            ErrorCode::PermissionDenied
+        );
+        self.pool_count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod permission_test {
+    use super::*;
+
+    #[test]
+    fn use_for_pool_test() {
+        let amm_config = Pubkey::new_unique();
+        let mut permission = Permission::default();
+        // unrestricted
+        permission
+            .use_for_pool(amm_config, &CreatorFeeOn::OnlyToken1, 10_000, u64::MAX)
+            .unwrap();
+
+        permission.allowed_amm_configs[1] = amm_config;
+        permission.expire_time = 1000;
+        permission.max_pool_count = 2;
+        permission.max_creator_fee_rate = 1000;
+        permission.allowed_creator_fee_on = 0b011;
+        assert!(permission
+            .use_for_pool(Pubkey::new_unique(), &CreatorFeeOn::BothToken, 1000, 999)
+            .is_err());
+        assert!(permission
+            .use_for_pool(amm_config, &CreatorFeeOn::OnlyToken1, 1000, 999)
+            .is_err());
+        assert!(permission
+            .use_for_pool(amm_config, &CreatorFeeOn::OnlyToken0, 1001, 999)
+            .is_err());
+        assert!(permission
+            .use_for_pool(amm_config, &CreatorFeeOn::OnlyToken0, 1000, 1000)
+            .is_err());
+        assert_eq!(permission.pool_count, 1);
+        permission
+            .use_for_pool(amm_config, &CreatorFeeOn::OnlyToken0, 1000, 999)
+            .unwrap();
+        assert_eq!(permission.pool_count, 2);
+        // the quota is used up
+        assert!(permission
+            .use_for_pool(amm_config, &CreatorFeeOn::BothToken, 0, 0)
+            .is_err());
+    }
 }
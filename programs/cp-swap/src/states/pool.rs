@@ -1,4 +1,9 @@
-use crate::{curve::TradeDirection, error::ErrorCode};
+use super::AmmConfig;
+use crate::{
+    curve::{fees::FEE_RATE_DENOMINATOR_VALUE, TradeDirection},
+    error::ErrorCode,
+    utils::U256,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
 use std::ops::{BitAnd, BitOr, BitXor};
@@ -6,8 +11,17 @@ use std::ops::{BitAnd, BitOr, BitXor};
 pub const POOL_SEED: &str = "pool";
 pub const POOL_LP_MINT_SEED: &str = "pool_lp_mint";
 pub const POOL_VAULT_SEED: &str = "pool_vault";
+/// The default lp amount locked forever at initialization, so `lp_supply` never reaches zero
+pub const LOCK_LP_AMOUNT: u64 = 100;
+/// The max lp amount an amm config can lock, one lp token
+pub const MAX_LOCK_LP_AMOUNT: u64 = 1_000_000_000;
+/// The max share of the initial liquidity an amm config can lock, 1%
+pub const MAX_LOCK_LP_RATE: u64 = 10_000;
+/// The max delay in seconds of the open time of a pool by its creator, at a time
+pub const MAX_OPEN_TIME_DELAY: u64 = 7 * 24 * 3600;
 
 pub const Q32: u128 = (u32::MAX as u128) + 1; // 2^32
+pub const Q64: u128 = (u64::MAX as u128) + 1; // 2^64
 
 pub enum PoolStatusBitIndex {
     Deposit,
@@ -51,6 +65,18 @@ impl CreatorFeeOn {
     }
 }
 
+/// A change of the pool settings managed by the pool creator
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum PoolCreatorSettingsUpdate {
+    /// Can only be lowered, 0 when inheriting the rate of the amm config
+    CreatorFeeRate(u64),
+    /// Can only be turned off
+    EnableCreatorFee(bool),
+    CreatorFeeOn(CreatorFeeOn),
+    /// Can only be delayed before the pool opens, by `MAX_OPEN_TIME_DELAY` at most
+    OpenTime(u64),
+}
+
 pub struct SwapParams {
     pub trade_direction: TradeDirection,
     pub total_input_token_amount: u64,
@@ -101,7 +127,8 @@ pub struct PoolState {
     pub mint_0_decimals: u8,
     pub mint_1_decimals: u8,
 
-    /// True circulating supply without burns and lock ups
+    /// True lp supply without burns, including the `lock_lp_amount` that is never minted,
+    /// so the circulating supply is `lp_supply - lock_lp_amount()`
     pub lp_supply: u64,
     /// The amounts of token_0 and token_1 that are owed to the liquidity provider.
     pub protocol_fees_token_0: u64,
@@ -124,17 +151,66 @@ pub struct PoolState {
     pub padding1: [u8; 6],
     pub creator_fees_token_0: u64,
     pub creator_fees_token_1: u64,
+    /// The optional hourly oracle history account, default if not created
+    pub observation_history_key: Pubkey,
+    /// The slot or timestamp the current price breaker window started at
+    pub price_breaker_window_start: u64,
+    /// The token_0 price at the start of the price breaker window, Q64.64
+    pub price_breaker_reference_price_x64: u128,
+    /// The trade directions tripped in the current window
+    /// bit0: ZeroForOne, token_0 price moved down
+    /// bit1: OneForZero, token_0 price moved up
+    pub price_breaker_tripped: u8,
+    /// Whether `reserve_0` and `reserve_1` are tracked, pools created before are
+    /// tracked from their first deposit, withdraw, swap or sync
+    pub reserves_tracked: bool,
+    /// Whether the pool was created with a permission, pools created before aren't flagged
+    pub with_permission: bool,
+    pub padding2: [u8; 5],
+    /// The timestamp until which each disabled status bit is disabled, indexed by `PoolStatusBitIndex`
+    /// 0: disabled until enabled again
+    pub status_pause_until: [u64; 3],
+    /// The proposed pool creator, default if no transfer is pending
+    pub pending_pool_creator: Pubkey,
+    /// The creator fee split account, default if the pool creator collects the creator fees
+    pub creator_fee_split: Pubkey,
+    /// The creator fee rate of the pool, capped by the amm config max, 0 to inherit the amm config one
+    pub creator_fee_rate: u64,
+    /// The token_0 and token_1 amounts backing the lp supply, tokens sent directly to the vaults aren't counted
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+    /// The lp amount locked at initialization, 0 for pools created with `LOCK_LP_AMOUNT`
+    pub lock_lp_amount: u64,
     /// padding for future updates
-    pub padding: [u64; 28],
+    pub padding: [u64; 5],
 }
 
 impl PoolState {
-    pub const LEN: usize = 8 + 10 * 32 + 1 * 5 + 8 * 7 + 1 * 2 + 6 * 1 + 2 * 8 + 8 * 28;
+    pub const LEN: usize = 8
+        + 10 * 32
+        + 1 * 5
+        + 8 * 7
+        + 1 * 2
+        + 6 * 1
+        + 2 * 8
+        + 32
+        + 8
+        + 16
+        + 1 * 3
+        + 5
+        + 8 * 3
+        + 32 * 2
+        + 8
+        + 8 * 2
+        + 8
+        + 8 * 5;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         &mut self,
         auth_bump: u8,
         lp_supply: u64,
+        lock_lp_amount: u64,
         open_time: u64,
         pool_creator: Pubkey,
         amm_config: Pubkey,
@@ -174,30 +250,180 @@ impl PoolState {
         self.padding1 = [0u8; 6];
         self.creator_fees_token_0 = 0;
         self.creator_fees_token_1 = 0;
-        self.padding = [0u64; 28];
+        self.observation_history_key = Pubkey::default();
+        self.price_breaker_window_start = 0;
+        self.price_breaker_reference_price_x64 = 0;
+        self.price_breaker_tripped = 0;
+        self.reserves_tracked = false;
+        self.with_permission = false;
+        self.padding2 = [0u8; 5];
+        self.status_pause_until = [0u64; 3];
+        self.pending_pool_creator = Pubkey::default();
+        self.creator_fee_split = Pubkey::default();
+        self.creator_fee_rate = 0;
+        self.reserve_0 = 0;
+        self.reserve_1 = 0;
+        self.lock_lp_amount = lock_lp_amount;
+        self.padding = [0u64; 5];
+    }
+
+    /// The lp amount locked at initialization
+    pub fn lock_lp_amount(&self) -> u64 {
+        if self.lock_lp_amount == 0 {
+            LOCK_LP_AMOUNT
+        } else {
+            self.lock_lp_amount
+        }
+    }
+
+    /// Whether fees are still owed to the protocol, the fund or the pool creator
+    pub fn has_uncollected_fees(&self) -> bool {
+        self.protocol_fees_token_0 != 0
+            || self.protocol_fees_token_1 != 0
+            || self.fund_fees_token_0 != 0
+            || self.fund_fees_token_1 != 0
+            || self.creator_fees_token_0 != 0
+            || self.creator_fees_token_1 != 0
+    }
+
+    /// Checks the liquidity is all withdrawn but the locked lp amount, and the fees all collected
+    pub fn check_closable(&self) -> Result<()> {
+        require!(
+            self.lp_supply == self.lock_lp_amount() && !self.has_uncollected_fees(),
+            ErrorCode::PoolNotEmpty
+        );
+        Ok(())
+    }
+
+    /// Checks the pool creator can still cancel the pool: before `open_time`, holding
+    /// the whole minted lp supply, since deposits are open before it, and without fees owed
+    pub fn check_cancellable(
+        &self,
+        block_timestamp: u64,
+        creator_lp_amount: u64,
+        lp_mint_supply: u64,
+    ) -> Result<()> {
+        require_gt!(self.open_time, block_timestamp, ErrorCode::NotApproved);
+        require!(
+            creator_lp_amount == lp_mint_supply && !self.has_uncollected_fees(),
+            ErrorCode::PoolNotEmpty
+        );
+        Ok(())
     }
 
+    /// Sets the status, the changed bits lose their timed pause and the newly disabled ones stay
+    /// disabled until enabled again. The unchanged bits keep their timed pause, so writing back the
+    /// stored status doesn't turn an expired pause into an indefinite one
     pub fn set_status(&mut self, status: u8) {
-        self.status = status
+        let changed = self.status.bitxor(status);
+        for index in 0..3 {
+            if changed.bitand(1u8 << index) != 0 {
+                self.status_pause_until[index] = 0;
+            }
+        }
+        self.status = status;
+    }
+
+    /// Disables the status bits until `pause_until`, the other bits are left unchanged
+    pub fn pause_until(&mut self, status: u8, pause_until: u64) {
+        for index in 0..3 {
+            let s = 1u8 << index;
+            if status.bitand(s) != 0 {
+                self.status = self.status.bitor(s);
+                self.status_pause_until[index] = pause_until;
+            }
+        }
+    }
+
+    /// The status bits disabled at the timestamp, without the expired pauses
+    pub fn status_at(&self, block_timestamp: u64) -> u8 {
+        let mut status = self.status;
+        let status_pause_until = self.status_pause_until;
+        for (index, pause_until) in status_pause_until.into_iter().enumerate() {
+            if pause_until != 0 && block_timestamp >= pause_until {
+                status = status.bitand(u8::MAX.bitxor(1u8 << index));
+            }
+        }
+        status
     }
 
     pub fn set_status_by_bit(&mut self, bit: PoolStatusBitIndex, flag: PoolStatusBitFlag) {
-        let s = u8::from(1) << (bit as u8);
+        let index = bit as usize;
+        let s = 1u8 << index;
+        self.status_pause_until[index] = 0;
         if flag == PoolStatusBitFlag::Disable {
             self.status = self.status.bitor(s);
         } else {
-            let m = u8::from(255).bitxor(s);
+            let m = u8::MAX.bitxor(s);
             self.status = self.status.bitand(m);
         }
     }
 
-    /// Get status by bit, if it is `noraml` status, return true
-    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
-        let status = u8::from(1) << (bit as u8);
-        self.status.bitand(status) == 0
+    /// Get status by bit, if it is `noraml` status or the pause expired, return true
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex, block_timestamp: u64) -> bool {
+        let status = 1u8 << (bit as u8);
+        self.status_at(block_timestamp).bitand(status) == 0
     }
 
+    /// The pool reserves, the tracked ones if the pool has them,
+    /// or else the vault amounts without the protocol, fund and creator fees
     pub fn vault_amount_without_fee(&self, vault_0: u64, vault_1: u64) -> Result<(u64, u64)> {
+        if self.reserves_tracked {
+            return Ok((self.reserve_0, self.reserve_1));
+        }
+        self.untracked_vault_amount(vault_0, vault_1)
+    }
+
+    /// Sets the tracked reserves, starting to track them if the pool didn't
+    pub fn set_reserves(&mut self, reserve_0: u64, reserve_1: u64) {
+        self.reserve_0 = reserve_0;
+        self.reserve_1 = reserve_1;
+        self.reserves_tracked = true;
+    }
+
+    /// Sets the reserves to the vault amounts without the fees, absorbing the excess
+    pub fn sync_reserves(&mut self, vault_0: u64, vault_1: u64) -> Result<()> {
+        let (reserve_0, reserve_1) = self.untracked_vault_amount(vault_0, vault_1)?;
+        self.set_reserves(reserve_0, reserve_1);
+        Ok(())
+    }
+
+    /// Scales down the larger side of the amounts to the ratio of the reserves
+    pub fn proportional_amounts(
+        &self,
+        vault_0: u64,
+        vault_1: u64,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Result<(u64, u64)> {
+        let (reserve_0, reserve_1) = self.vault_amount_without_fee(vault_0, vault_1)?;
+        let (reserve_0, reserve_1) = (u128::from(reserve_0), u128::from(reserve_1));
+        require!(reserve_0 > 0 && reserve_1 > 0, ErrorCode::EmptySupply);
+        if u128::from(amount_0) * reserve_1 <= u128::from(amount_1) * reserve_0 {
+            let amount_1 = u128::from(amount_0) * reserve_1 / reserve_0;
+            Ok((amount_0, u64::try_from(amount_1).unwrap()))
+        } else {
+            let amount_0 = u128::from(amount_1) * reserve_0 / reserve_1;
+            Ok((u64::try_from(amount_0).unwrap(), amount_1))
+        }
+    }
+
+    /// The vault amounts neither in the reserves nor owed as fees, e.g. tokens sent directly to the vaults
+    pub fn excess_vault_amount(&self, vault_0: u64, vault_1: u64) -> Result<(u64, u64)> {
+        let (untracked_0, untracked_1) = self.untracked_vault_amount(vault_0, vault_1)?;
+        let (reserve_0, reserve_1) = self.vault_amount_without_fee(vault_0, vault_1)?;
+        Ok((
+            untracked_0
+                .checked_sub(reserve_0)
+                .ok_or(ErrorCode::InsufficientVault)?,
+            untracked_1
+                .checked_sub(reserve_1)
+                .ok_or(ErrorCode::InsufficientVault)?,
+        ))
+    }
+
+    /// The vault amounts without the protocol, fund and creator fees
+    fn untracked_vault_amount(&self, vault_0: u64, vault_1: u64) -> Result<(u64, u64)> {
         let fees_token_0 = self
             .protocol_fees_token_0
             .checked_add(self.fund_fees_token_0)
@@ -228,6 +454,95 @@ impl PoolState {
         ))
     }
 
+    /// Q64.64 prices, `u64::MAX * 2^64` still fits in a u128
+    pub fn token_price_x64(&self, vault_0: u64, vault_1: u64) -> Result<(u128, u128)> {
+        let (token_0_amount, token_1_amount) = self.vault_amount_without_fee(vault_0, vault_1)?;
+        Ok((
+            token_1_amount as u128 * Q64 / token_0_amount as u128,
+            token_0_amount as u128 * Q64 / token_1_amount as u128,
+        ))
+    }
+
+    /// Q64.64 values of one LP token unit, in token_0 and in token_1, from the fair reserves
+    /// `sqrt(k / p)` and `sqrt(k * p)` at the given prices instead of the spot reserves,
+    /// so moving the spot price can't inflate them: `2 * sqrt(k * p) / lp_supply`.
+    pub fn fair_lp_price_x64(
+        &self,
+        vault_0: u64,
+        vault_1: u64,
+        token_0_price_x64: u128,
+        token_1_price_x64: u128,
+    ) -> Result<(u128, u128)> {
+        require_gt!(self.lp_supply, 0, ErrorCode::ZeroTradingTokens);
+        let (token_0_amount, token_1_amount) = self.vault_amount_without_fee(vault_0, vault_1)?;
+        let constant = U256::from(token_0_amount) * U256::from(token_1_amount);
+        let lp_price_x64 = |price_x64: u128| -> Result<u128> {
+            // sqrt(k * p * 2^64) = sqrt(k * p) * 2^32
+            let root = constant
+                .checked_mul(U256::from(price_x64))
+                .ok_or(ErrorCode::MathOverflow)?
+                .integer_sqrt();
+            let lp_price_x64 = (root << 33) / U256::from(self.lp_supply);
+            if lp_price_x64 > U256::from(u128::MAX) {
+                return err!(ErrorCode::MathOverflow);
+            }
+            Ok(lp_price_x64.as_u128())
+        };
+        Ok((
+            lp_price_x64(token_1_price_x64)?,
+            lp_price_x64(token_0_price_x64)?,
+        ))
+    }
+
+    /// Price movement circuit breaker. A new window starts `window` slots or seconds after
+    /// the last one, with the price before the swap as reference. The swap moving the token_0
+    /// price more than `max_price_move_rate` from it succeeds and trips its direction, then
+    /// further swaps in that direction are rejected until the window resets.
+    /// The reference is kept for the whole window rather than taken from the last observation,
+    /// observations are written at most once per update duration, and a reference following
+    /// every swap would let a series of small swaps walk the price past the limit.
+    /// `now` going backwards means the config switched between slots and seconds, the window
+    /// resets then. Returns whether this swap tripped the breaker.
+    pub fn check_price_move(
+        &mut self,
+        now: u64,
+        window: u64,
+        max_price_move_rate: u64,
+        trade_direction: TradeDirection,
+        price_before_x64: u128,
+        price_after_x64: u128,
+    ) -> Result<bool> {
+        if max_price_move_rate == 0 {
+            return Ok(false);
+        }
+        if self.price_breaker_reference_price_x64 == 0
+            || now < self.price_breaker_window_start
+            || now
+                >= self
+                    .price_breaker_window_start
+                    .saturating_add(window.max(1))
+        {
+            self.price_breaker_window_start = now;
+            self.price_breaker_reference_price_x64 = price_before_x64;
+            self.price_breaker_tripped = 0;
+        }
+        let direction_bit = match trade_direction {
+            TradeDirection::ZeroForOne => 1u8,
+            TradeDirection::OneForZero => 1u8 << 1,
+        };
+        if self.price_breaker_tripped & direction_bit != 0 {
+            return err!(ErrorCode::PriceMoveLimitExceeded);
+        }
+        let reference_price_x64 = self.price_breaker_reference_price_x64;
+        let price_move = U256::from(price_after_x64.abs_diff(reference_price_x64))
+            * U256::from(FEE_RATE_DENOMINATOR_VALUE);
+        if price_move > U256::from(reference_price_x64) * U256::from(max_price_move_rate) {
+            self.price_breaker_tripped |= direction_bit;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     pub fn update_lp_supply(
         &mut self,
         liquidity_delta: u64,
@@ -278,7 +593,7 @@ impl PoolState {
             let (total_input_token_amount, total_output_token_amount) =
                 self.vault_amount_without_fee(input_vault_amount, output_vault_amount)?;
             let (token_0_price_x64, token_1_price_x64) =
-                self.token_price_x32(input_vault_amount, output_vault_amount)?;
+                self.token_price_x64(input_vault_amount, output_vault_amount)?;
 
             (
                 TradeDirection::ZeroForOne,
@@ -292,7 +607,7 @@ impl PoolState {
             let (total_output_token_amount, total_input_token_amount) =
                 self.vault_amount_without_fee(output_vault_amount, input_vault_amount)?;
             let (token_0_price_x64, token_1_price_x64) =
-                self.token_price_x32(output_vault_amount, input_vault_amount)?;
+                self.token_price_x64(output_vault_amount, input_vault_amount)?;
 
             (
                 TradeDirection::OneForZero,
@@ -315,14 +630,85 @@ impl PoolState {
         })
     }
 
-    pub fn adjust_creator_fee_rate(&self, creator_fee_rate: u64) -> u64 {
-        if self.enable_creator_fee {
-            creator_fee_rate
-        } else {
+    /// Whether moving the pool from `amm_config` to `new_amm_config` raises one of
+    /// the fee rates timelocked for the amm configs, see `AmmConfigUpdate::is_timelocked_raise`
+    pub fn is_fee_raise(&self, amm_config: &AmmConfig, new_amm_config: &AmmConfig) -> bool {
+        new_amm_config.trade_fee_rate > amm_config.trade_fee_rate
+            || new_amm_config.protocol_fee_rate > amm_config.protocol_fee_rate
+            || self.adjust_creator_fee_rate(new_amm_config)
+                > self.adjust_creator_fee_rate(amm_config)
+    }
+
+    /// Proposes a new pool creator, the default address cancels a pending transfer
+    pub fn propose_pool_creator(&mut self, new_creator: Pubkey) {
+        self.pending_pool_creator = new_creator;
+    }
+
+    /// Makes the pending pool creator the pool creator, returns the previous one
+    pub fn accept_pool_creator(&mut self, new_creator: Pubkey) -> Result<Pubkey> {
+        require!(
+            new_creator != Pubkey::default() && new_creator == self.pending_pool_creator,
+            ErrorCode::InvalidOwner
+        );
+        self.pending_pool_creator = Pubkey::default();
+        Ok(std::mem::replace(&mut self.pool_creator, new_creator))
+    }
+
+    /// The creator fee rate charged by the pool
+    pub fn adjust_creator_fee_rate(&self, amm_config: &AmmConfig) -> u64 {
+        if !self.enable_creator_fee {
             0
+        } else if self.creator_fee_rate != 0 {
+            self.creator_fee_rate.min(amm_config.max_creator_fee_rate())
+        } else {
+            amm_config.creator_fee_rate
         }
     }
 
+    /// Applies a change of the pool creator settings, returning the old value
+    pub fn update_creator_settings(
+        &mut self,
+        update: PoolCreatorSettingsUpdate,
+        amm_config: &AmmConfig,
+        block_timestamp: u64,
+    ) -> Result<PoolCreatorSettingsUpdate> {
+        Ok(match update {
+            PoolCreatorSettingsUpdate::CreatorFeeRate(creator_fee_rate) => {
+                let old_value = self.adjust_creator_fee_rate(amm_config);
+                require!(self.enable_creator_fee, ErrorCode::NotApproved);
+                require!(
+                    creator_fee_rate != 0 && creator_fee_rate <= old_value,
+                    ErrorCode::InvalidFeeRate
+                );
+                self.creator_fee_rate = creator_fee_rate;
+                PoolCreatorSettingsUpdate::CreatorFeeRate(old_value)
+            }
+            PoolCreatorSettingsUpdate::EnableCreatorFee(enable_creator_fee) => {
+                require!(!enable_creator_fee, ErrorCode::NotApproved);
+                let old_value = self.enable_creator_fee;
+                self.enable_creator_fee = false;
+                PoolCreatorSettingsUpdate::EnableCreatorFee(old_value)
+            }
+            PoolCreatorSettingsUpdate::CreatorFeeOn(creator_fee_on) => {
+                let old_value = CreatorFeeOn::from_u8(self.creator_fee_on)?;
+                self.creator_fee_on = creator_fee_on.to_u8();
+                PoolCreatorSettingsUpdate::CreatorFeeOn(old_value)
+            }
+            PoolCreatorSettingsUpdate::OpenTime(open_time) => {
+                let old_value = self.open_time;
+                require_gt!(old_value, block_timestamp, ErrorCode::NotApproved);
+                require_gte!(open_time, old_value, ErrorCode::InvalidInput);
+                require_gte!(
+                    old_value.saturating_add(MAX_OPEN_TIME_DELAY),
+                    open_time,
+                    ErrorCode::InvalidInput
+                );
+                self.open_time = open_time;
+                PoolCreatorSettingsUpdate::OpenTime(old_value)
+            }
+        })
+    }
+
     pub fn update_fees(
         &mut self,
         protocol_fee: u64,
@@ -369,6 +755,27 @@ impl PoolState {
     }
 }
 
+/// The Q64.64 token_0 prices before and after the reserves change outside of a swap,
+/// with the direction of the swap that would move the price the same way,
+/// so that the change goes through the price breaker like a swap does
+pub fn reserves_change_price_x64(
+    reserve_0_before: u64,
+    reserve_1_before: u64,
+    reserve_0: u64,
+    reserve_1: u64,
+) -> Result<(TradeDirection, u128, u128)> {
+    require_gt!(reserve_0_before, 0, ErrorCode::ZeroTradingTokens);
+    require_gt!(reserve_0, 0, ErrorCode::ZeroTradingTokens);
+    let price_before_x64 = u128::from(reserve_1_before) * Q64 / u128::from(reserve_0_before);
+    let price_after_x64 = u128::from(reserve_1) * Q64 / u128::from(reserve_0);
+    let trade_direction = if price_after_x64 < price_before_x64 {
+        TradeDirection::ZeroForOne
+    } else {
+        TradeDirection::OneForZero
+    };
+    Ok((trade_direction, price_before_x64, price_after_x64))
+}
+
 #[cfg(test)]
 pub mod pool_test {
     use super::*;
@@ -378,6 +785,488 @@ pub mod pool_test {
         assert_eq!(std::mem::size_of::<PoolState>(), PoolState::LEN - 8)
     }
 
+    #[test]
+    fn reserves_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.protocol_fees_token_0 = 10;
+        pool_state.fund_fees_token_1 = 20;
+        // untracked reserves are the vault amounts without the fees
+        assert_eq!(
+            pool_state.vault_amount_without_fee(1_000, 2_000).unwrap(),
+            (990, 1_980)
+        );
+        assert_eq!(
+            pool_state.excess_vault_amount(1_000, 2_000).unwrap(),
+            (0, 0)
+        );
+
+        pool_state.set_reserves(990, 1_980);
+        // a donation doesn't move the reserves
+        assert_eq!(
+            pool_state.vault_amount_without_fee(1_500, 2_000).unwrap(),
+            (990, 1_980)
+        );
+        assert_eq!(
+            pool_state.excess_vault_amount(1_500, 2_000).unwrap(),
+            (500, 0)
+        );
+        assert!(pool_state.excess_vault_amount(900, 2_000).is_err());
+
+        pool_state.sync_reserves(1_500, 2_000).unwrap();
+        assert_eq!((pool_state.reserve_0, pool_state.reserve_1), (1_490, 1_980));
+        assert_eq!(
+            pool_state.excess_vault_amount(1_500, 2_000).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn proportional_amounts_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.set_reserves(1_000, 3_000);
+        assert_eq!(
+            pool_state.proportional_amounts(0, 0, 100, 900).unwrap(),
+            (100, 300)
+        );
+        assert_eq!(
+            pool_state.proportional_amounts(0, 0, 100, 200).unwrap(),
+            (66, 200)
+        );
+        assert_eq!(
+            pool_state.proportional_amounts(0, 0, 100, 0).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn check_closable_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.lock_lp_amount = 1000;
+        pool_state.lp_supply = 1001;
+        assert_eq!(
+            pool_state.check_closable().unwrap_err(),
+            ErrorCode::PoolNotEmpty.into()
+        );
+        // only the locked lp amount is left
+        pool_state.lp_supply = 1000;
+        pool_state.check_closable().unwrap();
+
+        // every fee has to be collected first
+        pool_state.protocol_fees_token_0 = 1;
+        assert!(pool_state.check_closable().is_err());
+        pool_state.protocol_fees_token_0 = 0;
+        pool_state.fund_fees_token_1 = 1;
+        assert!(pool_state.check_closable().is_err());
+        pool_state.fund_fees_token_1 = 0;
+        pool_state.creator_fees_token_0 = 1;
+        assert!(pool_state.check_closable().is_err());
+        pool_state.creator_fees_token_0 = 0;
+        pool_state.check_closable().unwrap();
+
+        // pools created before the lock was stored locked the default amount
+        let mut pool_state = PoolState::default();
+        pool_state.lp_supply = LOCK_LP_AMOUNT;
+        pool_state.check_closable().unwrap();
+    }
+
+    #[test]
+    fn check_cancellable_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.open_time = 1000;
+        pool_state.lp_supply = 1_000_100;
+        pool_state.lock_lp_amount = 100;
+
+        pool_state
+            .check_cancellable(999, 1_000_000, 1_000_000)
+            .unwrap();
+        // not once the pool is open
+        assert_eq!(
+            pool_state
+                .check_cancellable(1000, 1_000_000, 1_000_000)
+                .unwrap_err(),
+            ErrorCode::NotApproved.into()
+        );
+        assert!(pool_state
+            .check_cancellable(1001, 1_000_000, 1_000_000)
+            .is_err());
+        // nor once some lp tokens left the creator
+        assert_eq!(
+            pool_state
+                .check_cancellable(999, 999_999, 1_000_000)
+                .unwrap_err(),
+            ErrorCode::PoolNotEmpty.into()
+        );
+        pool_state.creator_fees_token_1 = 1;
+        assert!(pool_state
+            .check_cancellable(999, 1_000_000, 1_000_000)
+            .is_err());
+    }
+
+    #[test]
+    fn is_fee_raise_test() {
+        let mut amm_config = AmmConfig::default();
+        amm_config.trade_fee_rate = 2500;
+        amm_config.protocol_fee_rate = 120_000;
+        amm_config.creator_fee_rate = 500;
+        let mut pool_state = PoolState::default();
+        pool_state.enable_creator_fee = true;
+
+        let mut new_amm_config = amm_config.clone();
+        assert!(!pool_state.is_fee_raise(&amm_config, &new_amm_config));
+        new_amm_config.trade_fee_rate = 2000;
+        new_amm_config.fund_fee_rate = 400_000;
+        assert!(!pool_state.is_fee_raise(&amm_config, &new_amm_config));
+
+        new_amm_config.trade_fee_rate = 10_000;
+        assert!(pool_state.is_fee_raise(&amm_config, &new_amm_config));
+        new_amm_config.trade_fee_rate = 2500;
+        new_amm_config.protocol_fee_rate = 120_001;
+        assert!(pool_state.is_fee_raise(&amm_config, &new_amm_config));
+        new_amm_config.protocol_fee_rate = 120_000;
+        new_amm_config.creator_fee_rate = 600;
+        assert!(pool_state.is_fee_raise(&amm_config, &new_amm_config));
+
+        // the creator fee rate set by the pool is kept if the new config allows it
+        pool_state.creator_fee_rate = 400;
+        new_amm_config.max_creator_fee_rate = 1000;
+        assert!(!pool_state.is_fee_raise(&amm_config, &new_amm_config));
+        // a pool without the creator fee doesn't charge it under any config
+        pool_state.creator_fee_rate = 0;
+        pool_state.enable_creator_fee = false;
+        assert!(!pool_state.is_fee_raise(&amm_config, &new_amm_config));
+    }
+
+    #[test]
+    fn transfer_pool_creator_test() {
+        let creator = Pubkey::new_unique();
+        let new_creator = Pubkey::new_unique();
+        let mut pool_state = PoolState::default();
+        pool_state.pool_creator = creator;
+
+        // nothing to accept before a proposal
+        assert_eq!(
+            pool_state.accept_pool_creator(new_creator).unwrap_err(),
+            ErrorCode::InvalidOwner.into()
+        );
+        assert!(pool_state.accept_pool_creator(Pubkey::default()).is_err());
+
+        pool_state.propose_pool_creator(new_creator);
+        // the creator stays until the proposed one accepts
+        assert_eq!(pool_state.pool_creator, creator);
+        assert!(pool_state
+            .accept_pool_creator(Pubkey::new_unique())
+            .is_err());
+        assert_eq!(
+            pool_state.accept_pool_creator(new_creator).unwrap(),
+            creator
+        );
+        assert_eq!(pool_state.pool_creator, new_creator);
+        assert_eq!(pool_state.pending_pool_creator, Pubkey::default());
+        // a proposal is accepted once
+        assert!(pool_state.accept_pool_creator(new_creator).is_err());
+
+        // proposing the default address cancels the transfer
+        pool_state.propose_pool_creator(creator);
+        pool_state.propose_pool_creator(Pubkey::default());
+        assert!(pool_state.accept_pool_creator(creator).is_err());
+        assert_eq!(pool_state.pool_creator, new_creator);
+    }
+
+    #[test]
+    fn adjust_creator_fee_rate_test() {
+        let mut amm_config = AmmConfig::default();
+        amm_config.creator_fee_rate = 500;
+        amm_config.max_creator_fee_rate = 2000;
+        let mut pool_state = PoolState::default();
+        pool_state.enable_creator_fee = true;
+
+        // 0 inherits the config rate, which follows config updates
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 500);
+        amm_config.creator_fee_rate = 400;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 400);
+
+        // a pool rate above the config one is charged up to the max
+        pool_state.creator_fee_rate = 1500;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 1500);
+        // and capped when the max is lowered after the pool creation
+        amm_config.max_creator_fee_rate = 1000;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 1000);
+        // without a max, the config rate is the cap
+        amm_config.max_creator_fee_rate = 0;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 400);
+
+        // pools without the creator fee charge nothing
+        pool_state.enable_creator_fee = false;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 0);
+    }
+
+    #[test]
+    fn update_creator_settings_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.enable_creator_fee = true;
+        pool_state.open_time = 1000;
+        let mut amm_config = AmmConfig::default();
+        amm_config.creator_fee_rate = 500;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 500);
+        assert!(pool_state
+            .update_creator_settings(
+                PoolCreatorSettingsUpdate::CreatorFeeRate(501),
+                &amm_config,
+                0
+            )
+            .is_err());
+        assert_eq!(
+            pool_state
+                .update_creator_settings(
+                    PoolCreatorSettingsUpdate::CreatorFeeRate(300),
+                    &amm_config,
+                    0
+                )
+                .unwrap(),
+            PoolCreatorSettingsUpdate::CreatorFeeRate(500)
+        );
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 300);
+        // capped by the amm config max
+        amm_config.max_creator_fee_rate = 200;
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 200);
+        amm_config.max_creator_fee_rate = 0;
+        assert!(pool_state
+            .update_creator_settings(PoolCreatorSettingsUpdate::CreatorFeeRate(0), &amm_config, 0)
+            .is_err());
+
+        assert_eq!(
+            pool_state
+                .update_creator_settings(
+                    PoolCreatorSettingsUpdate::CreatorFeeOn(CreatorFeeOn::OnlyToken1),
+                    &amm_config,
+                    0
+                )
+                .unwrap(),
+            PoolCreatorSettingsUpdate::CreatorFeeOn(CreatorFeeOn::BothToken)
+        );
+        assert_eq!(pool_state.creator_fee_on, 2);
+
+        assert!(pool_state
+            .update_creator_settings(PoolCreatorSettingsUpdate::OpenTime(999), &amm_config, 0)
+            .is_err());
+        assert!(pool_state
+            .update_creator_settings(
+                PoolCreatorSettingsUpdate::OpenTime(u64::MAX),
+                &amm_config,
+                0
+            )
+            .is_err());
+        pool_state
+            .update_creator_settings(PoolCreatorSettingsUpdate::OpenTime(2000), &amm_config, 999)
+            .unwrap();
+        let open_time = pool_state.open_time;
+        assert_eq!(open_time, 2000);
+        assert!(pool_state
+            .update_creator_settings(PoolCreatorSettingsUpdate::OpenTime(3000), &amm_config, 2000)
+            .is_err());
+
+        assert!(pool_state
+            .update_creator_settings(
+                PoolCreatorSettingsUpdate::EnableCreatorFee(true),
+                &amm_config,
+                0
+            )
+            .is_err());
+        pool_state
+            .update_creator_settings(
+                PoolCreatorSettingsUpdate::EnableCreatorFee(false),
+                &amm_config,
+                0,
+            )
+            .unwrap();
+        assert_eq!(pool_state.adjust_creator_fee_rate(&amm_config), 0);
+        assert!(pool_state
+            .update_creator_settings(
+                PoolCreatorSettingsUpdate::CreatorFeeRate(100),
+                &amm_config,
+                0
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn fair_lp_price_test() {
+        let mut pool_state = PoolState::default();
+        pool_state.lp_supply = 1_000;
+        // 1_000 token_0 and 4_000 token_1, fair price 4 token_1 per token_0
+        let (lp_price_0_x64, lp_price_1_x64) = pool_state
+            .fair_lp_price_x64(1_000, 4_000, 4 * Q64, Q64 / 4)
+            .unwrap();
+        assert_eq!(lp_price_0_x64, 2 * Q64);
+        assert_eq!(lp_price_1_x64, 8 * Q64);
+
+        // spot pushed to 16 token_1 per token_0 with the same k, the TWAP still at 4
+        let (lp_price_0_x64, lp_price_1_x64) = pool_state
+            .fair_lp_price_x64(500, 8_000, 4 * Q64, Q64 / 4)
+            .unwrap();
+        assert_eq!(lp_price_0_x64, 2 * Q64);
+        assert_eq!(lp_price_1_x64, 8 * Q64);
+    }
+
+    #[test]
+    fn price_breaker_test() {
+        let mut pool_state = PoolState::default();
+        // 10%
+        let max_price_move_rate = 100_000;
+        // the swap crossing the limit succeeds and trips its direction
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    100,
+                    10,
+                    max_price_move_rate,
+                    TradeDirection::ZeroForOne,
+                    Q64,
+                    Q64 / 2
+                )
+                .unwrap(),
+            true
+        );
+        assert!(pool_state
+            .check_price_move(
+                105,
+                10,
+                max_price_move_rate,
+                TradeDirection::ZeroForOne,
+                Q64 / 2,
+                Q64 / 4
+            )
+            .is_err());
+        // the other direction is still allowed
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    105,
+                    10,
+                    max_price_move_rate,
+                    TradeDirection::OneForZero,
+                    Q64 / 2,
+                    Q64
+                )
+                .unwrap(),
+            false
+        );
+        // the window resets with the current price as reference
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    110,
+                    10,
+                    max_price_move_rate,
+                    TradeDirection::ZeroForOne,
+                    Q64,
+                    Q64 * 95 / 100
+                )
+                .unwrap(),
+            false
+        );
+        let reference_price_x64 = pool_state.price_breaker_reference_price_x64;
+        assert_eq!(reference_price_x64, Q64);
+
+        // switching the window from seconds to slots resets it
+        pool_state.price_breaker_tripped = 1;
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    50,
+                    0,
+                    max_price_move_rate,
+                    TradeDirection::ZeroForOne,
+                    Q64,
+                    Q64 * 95 / 100
+                )
+                .unwrap(),
+            false
+        );
+        let window_start = pool_state.price_breaker_window_start;
+        assert_eq!(window_start, 50);
+    }
+
+    #[test]
+    fn sync_price_breaker_test() {
+        let mut pool_state = PoolState::default();
+        // 10%
+        let max_price_move_rate = 100_000;
+        pool_state.set_reserves(1_000, 1_000);
+
+        // tokens sent to the vault of token_0 and synced move the price like a swap
+        let (reserve_0_before, reserve_1_before) =
+            pool_state.vault_amount_without_fee(1_050, 1_000).unwrap();
+        pool_state.sync_reserves(1_050, 1_000).unwrap();
+        let (trade_direction, price_before_x64, price_after_x64) = reserves_change_price_x64(
+            reserve_0_before,
+            reserve_1_before,
+            pool_state.reserve_0,
+            pool_state.reserve_1,
+        )
+        .unwrap();
+        assert_eq!(trade_direction, TradeDirection::ZeroForOne);
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    100,
+                    10,
+                    max_price_move_rate,
+                    trade_direction,
+                    price_before_x64,
+                    price_after_x64
+                )
+                .unwrap(),
+            false
+        );
+
+        // the next transfer and sync crossing the limit trips the breaker
+        let (reserve_0_before, reserve_1_before) =
+            pool_state.vault_amount_without_fee(1_200, 1_000).unwrap();
+        pool_state.sync_reserves(1_200, 1_000).unwrap();
+        let (trade_direction, price_before_x64, price_after_x64) = reserves_change_price_x64(
+            reserve_0_before,
+            reserve_1_before,
+            pool_state.reserve_0,
+            pool_state.reserve_1,
+        )
+        .unwrap();
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    101,
+                    10,
+                    max_price_move_rate,
+                    trade_direction,
+                    price_before_x64,
+                    price_after_x64
+                )
+                .unwrap(),
+            true
+        );
+        // so that no further sync can move the price that way in the window
+        let (trade_direction, price_before_x64, price_after_x64) =
+            reserves_change_price_x64(1_200, 1_000, 1_300, 1_000).unwrap();
+        assert_eq!(
+            pool_state
+                .check_price_move(
+                    102,
+                    10,
+                    max_price_move_rate,
+                    trade_direction,
+                    price_before_x64,
+                    price_after_x64
+                )
+                .unwrap_err(),
+            ErrorCode::PriceMoveLimitExceeded.into()
+        );
+
+        assert_eq!(
+            reserves_change_price_x64(0, 1_000, 1_000, 1_000).unwrap_err(),
+            ErrorCode::ZeroTradingTokens.into()
+        );
+    }
+
     mod pool_status_test {
         use super::*;
 
@@ -386,77 +1275,122 @@ pub mod pool_test {
             let mut pool_state = PoolState::default();
             pool_state.set_status(4); // 0000100
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, 0),
                 true
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw, 0),
                 true
             );
 
             // disable -> disable, nothing to change
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
                 false
             );
 
             // disable -> enable
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable);
-            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
+                true
+            );
 
             // enable -> enable, nothing to change
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable);
-            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
+                true
+            );
             // enable -> disable
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
                 false
             );
 
             pool_state.set_status(5); // 0000101
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, 0),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw, 0),
                 true
             );
 
             pool_state.set_status(7); // 0000111
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, 0),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw, 0),
                 false
             );
 
             pool_state.set_status(3); // 0000011
-            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 0),
+                true
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, 0),
                 false
             );
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw),
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Withdraw, 0),
                 false
             );
         }
+
+        #[test]
+        fn pause_until_test() {
+            let mut pool_state = PoolState::default();
+            pool_state.set_status(1); // 0000001
+            pool_state.pause_until(4, 1000); // 0000100
+            assert_eq!(pool_state.status, 5);
+            assert_eq!(pool_state.status_at(999), 5);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 999),
+                false
+            );
+            // the timed pause expires, the deposit stays disabled
+            assert_eq!(pool_state.status_at(1000), 1);
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap, 1000),
+                true
+            );
+            assert_eq!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::Deposit, u64::MAX),
+                false
+            );
+            // disabling again without a time makes it indefinite
+            pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
+            assert_eq!(pool_state.status_at(u64::MAX), 5);
+            pool_state.pause_until(2, 2000);
+            pool_state.set_status(0);
+            assert_eq!(pool_state.status_at(0), 0);
+            let status_pause_until = pool_state.status_pause_until;
+            assert_eq!(status_pause_until, [0u64; 3]);
+
+            // writing back the stored status keeps the expired pause expired
+            pool_state.pause_until(4, 1000);
+            pool_state.set_status(pool_state.status | 1);
+            assert_eq!(pool_state.status_at(1000), 1);
+        }
     }
 }
@@ -0,0 +1,106 @@
+use super::CONFIG_CHANGE_MIN_DELAY;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const POOL_CONFIG_MIGRATION_SEED: &str = "pool_config_migration";
+
+/// A migration of a pool to an amm config with higher fees, scheduled by the fee manager.
+/// Like the fee rate raises of an amm config, it can only be applied after `CONFIG_CHANGE_MIN_DELAY`
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolConfigMigration {
+    /// Bump to identify PDA
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    /// The amm config the pool is moved to
+    pub new_amm_config: Pubkey,
+    /// The time from which the migration can be applied
+    pub effective_time: u64,
+    /// padding
+    pub padding: [u64; 4],
+}
+
+impl PoolConfigMigration {
+    pub const LEN: usize = 8 + 1 + 32 * 2 + 8 + 8 * 4;
+
+    pub fn schedule(
+        &mut self,
+        pool_id: Pubkey,
+        new_amm_config: Pubkey,
+        effective_time: u64,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        require_gte!(
+            effective_time,
+            block_timestamp + CONFIG_CHANGE_MIN_DELAY,
+            ErrorCode::InvalidInput
+        );
+        self.pool_id = pool_id;
+        self.new_amm_config = new_amm_config;
+        self.effective_time = effective_time;
+        Ok(())
+    }
+
+    /// Checks the migration of `pool_id` to `new_amm_config` is scheduled and its delay is over
+    pub fn check_ready(
+        &self,
+        pool_id: Pubkey,
+        new_amm_config: Pubkey,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        require_keys_eq!(self.pool_id, pool_id, ErrorCode::InvalidInput);
+        require_keys_eq!(self.new_amm_config, new_amm_config, ErrorCode::InvalidInput);
+        require!(
+            block_timestamp >= self.effective_time,
+            ErrorCode::ConfigChangeNotReady
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod pool_config_migration_test {
+    use super::*;
+
+    #[test]
+    fn schedule_test() {
+        let pool_id = Pubkey::new_unique();
+        let new_amm_config = Pubkey::new_unique();
+        let now = 1_700_000_000;
+        let mut migration = PoolConfigMigration::default();
+
+        assert_eq!(
+            migration
+                .schedule(
+                    pool_id,
+                    new_amm_config,
+                    now + CONFIG_CHANGE_MIN_DELAY - 1,
+                    now
+                )
+                .unwrap_err(),
+            ErrorCode::InvalidInput.into()
+        );
+        let effective_time = now + CONFIG_CHANGE_MIN_DELAY;
+        migration
+            .schedule(pool_id, new_amm_config, effective_time, now)
+            .unwrap();
+
+        // rejected before the delay
+        assert_eq!(
+            migration
+                .check_ready(pool_id, new_amm_config, effective_time - 1)
+                .unwrap_err(),
+            ErrorCode::ConfigChangeNotReady.into()
+        );
+        // accepted after it, only for the scheduled pool and config
+        migration
+            .check_ready(pool_id, new_amm_config, effective_time)
+            .unwrap();
+        assert!(migration
+            .check_ready(pool_id, Pubkey::new_unique(), effective_time)
+            .is_err());
+        assert!(migration
+            .check_ready(Pubkey::new_unique(), new_amm_config, effective_time)
+            .is_err());
+    }
+}
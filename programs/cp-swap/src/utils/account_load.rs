@@ -1,6 +1,6 @@
 use anchor_lang::{
     error::{Error, ErrorCode},
-    solana_program::{account_info::AccountInfo, pubkey::Pubkey},
+    solana_program::{account_info::AccountInfo, pubkey::Pubkey, system_program},
     Key, Owner, Result, ToAccountInfos, ZeroCopy,
 };
 use arrayref::array_ref;
@@ -167,3 +167,16 @@ impl<'info, T: ZeroCopy + Owner> Key for AccountLoad<'info, T> {
         *self.acc_info.key
     }
 }
+
+/// Closes an account owned by the program, returning its lamports to `destination`.
+pub fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = account.lamports();
+    **destination.lamports.borrow_mut() = destination.lamports().checked_add(lamports).unwrap();
+    **account.lamports.borrow_mut() = 0;
+
+    account.assign(&system_program::ID);
+    account.resize(0).map_err(Into::into)
+}
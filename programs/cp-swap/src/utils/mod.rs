@@ -1,7 +1,9 @@
 pub mod account_load;
 pub mod math;
+pub mod pool;
 pub mod token;
 
 pub use account_load::*;
 pub use math::*;
+pub use pool::*;
 pub use token::*;
@@ -0,0 +1,88 @@
+use crate::error::ErrorCode;
+use crate::utils::{close_program_account, close_token_account, transfer_from_pool_vault_to_user};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The accounts closed with a pool by `close_pool` and `cancel_pool`
+pub struct PoolAccountsToClose<'b, 'info> {
+    /// Pool vault and lp mint authority
+    pub vault_authority: AccountInfo<'info>,
+    pub auth_bump: u8,
+    pub token_0_vault: &'b InterfaceAccount<'info, TokenAccount>,
+    pub token_1_vault: &'b InterfaceAccount<'info, TokenAccount>,
+    pub vault_0_mint: &'b InterfaceAccount<'info, Mint>,
+    pub vault_1_mint: &'b InterfaceAccount<'info, Mint>,
+    pub token_program: &'b Program<'info, Token>,
+    pub token_program_2022: &'b Program<'info, Token2022>,
+    pub observation_state: AccountInfo<'info>,
+    /// The optional accounts of the pool, default if not created,
+    /// passed in this order as remaining accounts otherwise
+    pub observation_history_key: Pubkey,
+    pub creator_fee_split_key: Pubkey,
+}
+
+impl<'b, 'info> PoolAccountsToClose<'b, 'info> {
+    /// Sends what is left in the vaults to the recipients, then closes the vaults, the oracle accounts
+    /// and the creator fee split, returning the rent to `rent_receiver`. Returns the amounts sent.
+    pub fn close(
+        &self,
+        recipient_token_0: AccountInfo<'info>,
+        recipient_token_1: AccountInfo<'info>,
+        rent_receiver: &AccountInfo<'info>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<(u64, u64)> {
+        let signer_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[self.auth_bump]];
+        let amount_0 = self.token_0_vault.amount;
+        let amount_1 = self.token_1_vault.amount;
+        for (vault, mint, recipient, amount) in [
+            (
+                self.token_0_vault,
+                self.vault_0_mint,
+                recipient_token_0,
+                amount_0,
+            ),
+            (
+                self.token_1_vault,
+                self.vault_1_mint,
+                recipient_token_1,
+                amount_1,
+            ),
+        ] {
+            let token_program = if mint.to_account_info().owner == self.token_program.key {
+                self.token_program.to_account_info()
+            } else {
+                self.token_program_2022.to_account_info()
+            };
+            transfer_from_pool_vault_to_user(
+                self.vault_authority.clone(),
+                vault.to_account_info(),
+                recipient,
+                mint.to_account_info(),
+                token_program.clone(),
+                amount,
+                mint.decimals,
+                &[signer_seeds],
+            )?;
+            // Token-2022 vaults with withheld transfer fees must be harvested before they can be closed
+            close_token_account(
+                self.vault_authority.clone(),
+                token_program,
+                vault.to_account_info(),
+                rent_receiver.clone(),
+                &[signer_seeds],
+            )?;
+        }
+
+        close_program_account(&self.observation_state, rent_receiver)?;
+        let mut remaining_accounts = remaining_accounts.iter();
+        for key in [self.observation_history_key, self.creator_fee_split_key] {
+            if key != Pubkey::default() {
+                let account_info = remaining_accounts.next().ok_or(ErrorCode::InvalidInput)?;
+                require_keys_eq!(account_info.key(), key, ErrorCode::InvalidInput);
+                close_program_account(account_info, rent_receiver)?;
+            }
+        }
+        Ok((amount_0, amount_1))
+    }
+}
@@ -100,6 +100,25 @@ pub fn token_mint_to<'a>(
     )
 }
 
+/// Issue a spl_token `CloseAccount` instruction.
+pub fn close_token_account<'a>(
+    authority: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    account: AccountInfo<'a>,
+    destination: AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_2022::close_account(CpiContext::new_with_signer(
+        token_program,
+        token_2022::CloseAccount {
+            account,
+            destination,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
+
 pub fn token_burn<'a>(
     authority: AccountInfo<'a>,
     token_program: AccountInfo<'a>,
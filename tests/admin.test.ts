@@ -0,0 +1,86 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { RaydiumCpSwap } from "../target/types/raydium_cp_swap";
+import {
+  createAmmConfig,
+  createGlobalAdmin,
+  getGlobalAdminAddress,
+  updateAmmConfig,
+} from "./utils";
+import { assert } from "chai";
+
+describe("admin test", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const owner = anchor.Wallet.local().payer;
+
+  const program = anchor.workspace.RaydiumCpSwap as Program<RaydiumCpSwap>;
+
+  const confirmOptions = {
+    skipPreflight: true,
+  };
+
+  it("create global admin, the bootstrap admin holds every role", async () => {
+    const globalAdminAddress = await createGlobalAdmin(
+      program,
+      anchor.getProvider().connection,
+      owner,
+      confirmOptions
+    );
+    const [expectedAddress] = await getGlobalAdminAddress(program.programId);
+    assert(globalAdminAddress.equals(expectedAddress));
+
+    const globalAdmin = await program.account.globalAdmin.fetch(
+      globalAdminAddress
+    );
+    assert(globalAdmin.admin.equals(owner.publicKey));
+    assert(globalAdmin.pauseGuardian.equals(owner.publicKey));
+    assert(globalAdmin.feeManager.equals(owner.publicKey));
+  });
+
+  it("update amm config, the updated values are stored", async () => {
+    const configAddress = await createAmmConfig(
+      program,
+      anchor.getProvider().connection,
+      owner,
+      9,
+      new BN(2500),
+      new BN(120000),
+      new BN(40000),
+      new BN(0),
+      confirmOptions
+    );
+
+    await updateAmmConfig(
+      program,
+      owner,
+      configAddress,
+      { createPoolFee: { 0: new BN(100000000) } },
+      confirmOptions
+    );
+    let ammConfig = await program.account.ammConfig.fetch(configAddress);
+    assert(ammConfig.createPoolFee.eq(new BN(100000000)));
+    assert(ammConfig.tradeFeeRate.eq(new BN(2500)));
+
+    // fee rate decreases apply right away
+    await updateAmmConfig(
+      program,
+      owner,
+      configAddress,
+      { tradeFeeRate: { 0: new BN(2000) } },
+      confirmOptions
+    );
+    ammConfig = await program.account.ammConfig.fetch(configAddress);
+    assert(ammConfig.tradeFeeRate.eq(new BN(2000)));
+
+    await updateAmmConfig(
+      program,
+      owner,
+      configAddress,
+      { priceDeviationOnWithdraw: { 0: true } },
+      confirmOptions
+    );
+    ammConfig = await program.account.ammConfig.fetch(configAddress);
+    assert.equal(ammConfig.priceDeviationOnWithdraw, true);
+    assert(ammConfig.createPoolFee.eq(new BN(100000000)));
+  });
+});
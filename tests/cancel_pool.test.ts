@@ -0,0 +1,138 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { RaydiumCpSwap } from "../target/types/raydium_cp_swap";
+import {
+  cancelPool,
+  createAmmConfig,
+  createTokenMintAndAssociatedTokenAccount,
+  getBlockTimestamp,
+  getUserAndPoolVaultAmount,
+  initialize,
+} from "./utils";
+import { assert } from "chai";
+import { Keypair } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+
+describe("cancel pool test", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const owner = anchor.Wallet.local().payer;
+  const connection = anchor.getProvider().connection;
+
+  const program = anchor.workspace.RaydiumCpSwap as Program<RaydiumCpSwap>;
+
+  const confirmOptions = {
+    skipPreflight: true,
+  };
+
+  async function setupPool(openTimeDelay: number) {
+    const configAddress = await createAmmConfig(
+      program,
+      connection,
+      owner,
+      0,
+      new BN(10),
+      new BN(1000),
+      new BN(25000),
+      new BN(0),
+      confirmOptions
+    );
+    const [{ token0, token0Program }, { token1, token1Program }] =
+      await createTokenMintAndAssociatedTokenAccount(
+        connection,
+        owner,
+        new Keypair(),
+        { transferFeeBasisPoints: 0, MaxFee: 0 }
+      );
+    const openTime = new BN(
+      (await getBlockTimestamp(connection)) + openTimeDelay
+    );
+    return await initialize(
+      program,
+      owner,
+      configAddress,
+      token0,
+      token0Program,
+      token1,
+      token1Program,
+      confirmOptions,
+      undefined,
+      undefined,
+      openTime
+    );
+  }
+
+  it("cancel pool before open time, the initial liquidity goes back to the creator", async () => {
+    const { poolAddress, poolState } = await setupPool(3600);
+    const creatorLpToken = getAssociatedTokenAddressSync(
+      poolState.lpMint,
+      owner.publicKey,
+      false,
+      TOKEN_PROGRAM_ID
+    );
+    const {
+      onwerToken0Account: ownerToken0AccountBefore,
+      onwerToken1Account: ownerToken1AccountBefore,
+      poolVault0TokenAccount,
+      poolVault1TokenAccount,
+    } = await getUserAndPoolVaultAmount(
+      owner.publicKey,
+      poolState.token0Mint,
+      poolState.token0Program,
+      poolState.token1Mint,
+      poolState.token1Program,
+      poolState.token0Vault,
+      poolState.token1Vault
+    );
+    assert.isNotNull(await connection.getAccountInfo(creatorLpToken));
+
+    await cancelPool(program, owner, poolAddress, confirmOptions);
+
+    // everything in the vaults, including the share of the locked lp amount
+    const ownerToken0AccountAfter = await connection.getTokenAccountBalance(
+      ownerToken0AccountBefore.address
+    );
+    const ownerToken1AccountAfter = await connection.getTokenAccountBalance(
+      ownerToken1AccountBefore.address
+    );
+    assert.equal(
+      BigInt(ownerToken0AccountAfter.value.amount),
+      ownerToken0AccountBefore.amount + poolVault0TokenAccount.amount
+    );
+    assert.equal(
+      BigInt(ownerToken1AccountAfter.value.amount),
+      ownerToken1AccountBefore.amount + poolVault1TokenAccount.amount
+    );
+
+    for (const closed of [
+      poolAddress,
+      poolState.token0Vault,
+      poolState.token1Vault,
+      poolState.observationKey,
+      creatorLpToken,
+    ]) {
+      assert.isNull(await connection.getAccountInfo(closed));
+    }
+  });
+
+  it("cancel pool after open time, rejected", async () => {
+    const { poolAddress } = await setupPool(2);
+    await sleep(4000);
+
+    try {
+      await cancelPool(program, owner, poolAddress);
+      assert.fail("the pool is already open");
+    } catch (error) {
+      assert.include(String(error), "NotApproved");
+    }
+    assert.isNotNull(
+      await program.account.poolState.fetchNullable(poolAddress)
+    );
+  });
+});
+
+function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
@@ -0,0 +1,157 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { RaydiumCpSwap } from "../target/types/raydium_cp_swap";
+import { closePool, setupDepositTest, withdraw } from "./utils";
+import { assert } from "chai";
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+
+describe("close pool test", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const owner = anchor.Wallet.local().payer;
+  const connection = anchor.getProvider().connection;
+
+  const program = anchor.workspace.RaydiumCpSwap as Program<RaydiumCpSwap>;
+
+  const confirmOptions = {
+    skipPreflight: true,
+  };
+
+  it("close pool, rejected while it has liquidity", async () => {
+    const { poolAddress } = await setupDepositTest(
+      program,
+      connection,
+      owner,
+      {
+        config_index: 0,
+        tradeFeeRate: new BN(10),
+        protocolFeeRate: new BN(1000),
+        fundFeeRate: new BN(25000),
+        create_fee: new BN(0),
+      },
+      { transferFeeBasisPoints: 0, MaxFee: 0 }
+    );
+
+    try {
+      await closePool(program, owner, poolAddress);
+      assert.fail("the pool still has the initial liquidity");
+    } catch (error) {
+      assert.include(String(error), "PoolNotEmpty");
+    }
+    assert.isNotNull(
+      await program.account.poolState.fetchNullable(poolAddress)
+    );
+  });
+
+  it("close pool, the dust goes to the protocol and the rent to the pool creator", async () => {
+    const { poolAddress, poolState } = await setupDepositTest(
+      program,
+      connection,
+      owner,
+      {
+        config_index: 0,
+        tradeFeeRate: new BN(10),
+        protocolFeeRate: new BN(1000),
+        fundFeeRate: new BN(25000),
+        create_fee: new BN(0),
+      },
+      { transferFeeBasisPoints: 0, MaxFee: 0 }
+    );
+
+    // withdraw everything but the locked lp amount
+    const creatorLpToken = await getAccount(
+      connection,
+      getAssociatedTokenAddressSync(
+        poolState.lpMint,
+        owner.publicKey,
+        false,
+        TOKEN_PROGRAM_ID
+      )
+    );
+    await withdraw(
+      program,
+      owner,
+      poolState.ammConfig,
+      poolState.token0Mint,
+      poolState.token0Program,
+      poolState.token1Mint,
+      poolState.token1Program,
+      new BN(creatorLpToken.amount.toString()),
+      new BN(0),
+      new BN(0),
+      confirmOptions
+    );
+    const vault0 = await getAccount(
+      connection,
+      poolState.token0Vault,
+      "processed",
+      poolState.token0Program
+    );
+    const vault1 = await getAccount(
+      connection,
+      poolState.token1Vault,
+      "processed",
+      poolState.token1Program
+    );
+    assert(vault0.amount > BigInt(0));
+    assert(vault1.amount > BigInt(0));
+
+    // create the protocol token accounts first, so their rent isn't paid during the close
+    const ammConfig = await program.account.ammConfig.fetch(
+      poolState.ammConfig
+    );
+    const [protocolToken0, protocolToken1] = await Promise.all(
+      [
+        [poolState.token0Mint, poolState.token0Program],
+        [poolState.token1Mint, poolState.token1Program],
+      ].map(([mint, tokenProgram]) =>
+        getOrCreateAssociatedTokenAccount(
+          connection,
+          owner,
+          mint,
+          ammConfig.protocolOwner,
+          true,
+          "processed",
+          confirmOptions,
+          tokenProgram
+        )
+      )
+    );
+    assert.equal(protocolToken0.amount, BigInt(0));
+    assert.equal(protocolToken1.amount, BigInt(0));
+
+    const poolRent = await connection.getBalance(poolAddress);
+    const creatorBalanceBefore = await connection.getBalance(owner.publicKey);
+    await closePool(program, owner, poolAddress, confirmOptions);
+    const protocolAmount0 = (
+      await getAccount(
+        connection,
+        protocolToken0.address,
+        "processed",
+        poolState.token0Program
+      )
+    ).amount;
+    const protocolAmount1 = (
+      await getAccount(
+        connection,
+        protocolToken1.address,
+        "processed",
+        poolState.token1Program
+      )
+    ).amount;
+    assert.equal(protocolAmount0, vault0.amount);
+    assert.equal(protocolAmount1, vault1.amount);
+
+    assert.isNull(await program.account.poolState.fetchNullable(poolAddress));
+    assert.isNull(await connection.getAccountInfo(poolState.token0Vault));
+    assert.isNull(await connection.getAccountInfo(poolState.token1Vault));
+    assert.isNull(await connection.getAccountInfo(poolState.observationKey));
+    // the pool creator gets the rent of the pool, the vaults and the oracle back, less the fee
+    const creatorBalanceAfter = await connection.getBalance(owner.publicKey);
+    assert(creatorBalanceAfter > creatorBalanceBefore + poolRent - 10000);
+  });
+});
@@ -0,0 +1,85 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { RaydiumCpSwap } from "../target/types/raydium_cp_swap";
+import {
+  donate,
+  getUserAndPoolVaultAmount,
+  setupDepositTest,
+} from "./utils";
+import { assert } from "chai";
+
+describe("donate test", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const owner = anchor.Wallet.local().payer;
+
+  const program = anchor.workspace.RaydiumCpSwap as Program<RaydiumCpSwap>;
+
+  const confirmOptions = {
+    skipPreflight: true,
+  };
+
+  it("donate, the reserves grow without minting lp tokens", async () => {
+    const { poolAddress, poolState } = await setupDepositTest(
+      program,
+      anchor.getProvider().connection,
+      owner,
+      {
+        config_index: 0,
+        tradeFeeRate: new BN(10),
+        protocolFeeRate: new BN(1000),
+        fundFeeRate: new BN(25000),
+        create_fee: new BN(0),
+      },
+      { transferFeeBasisPoints: 0, MaxFee: 0 }
+    );
+
+    const {
+      poolVault0TokenAccount: poolVault0TokenAccountBefore,
+      poolVault1TokenAccount: poolVault1TokenAccountBefore,
+    } = await getUserAndPoolVaultAmount(
+      owner.publicKey,
+      poolState.token0Mint,
+      poolState.token0Program,
+      poolState.token1Mint,
+      poolState.token1Program,
+      poolState.token0Vault,
+      poolState.token1Vault
+    );
+
+    // 10_000_000_000 : 20_000_000_000, the proportional amounts are 1000 : 2000
+    await donate(
+      program,
+      owner,
+      poolAddress,
+      new BN(1000),
+      new BN(5000),
+      true,
+      confirmOptions
+    );
+    const newPoolState = await program.account.poolState.fetch(poolAddress);
+    assert(newPoolState.lpSupply.eq(poolState.lpSupply));
+
+    const {
+      poolVault0TokenAccount: poolVault0TokenAccountAfter,
+      poolVault1TokenAccount: poolVault1TokenAccountAfter,
+    } = await getUserAndPoolVaultAmount(
+      owner.publicKey,
+      poolState.token0Mint,
+      poolState.token0Program,
+      poolState.token1Mint,
+      poolState.token1Program,
+      poolState.token0Vault,
+      poolState.token1Vault
+    );
+    assert.equal(
+      poolVault0TokenAccountAfter.amount - poolVault0TokenAccountBefore.amount,
+      BigInt(1000)
+    );
+    assert.equal(
+      poolVault1TokenAccountAfter.amount - poolVault1TokenAccountBefore.amount,
+      BigInt(2000)
+    );
+    assert(newPoolState.reserve0.eq(poolState.reserve0.add(new BN(1000))));
+    assert(newPoolState.reserve1.eq(poolState.reserve1.add(new BN(2000))));
+  });
+});
@@ -0,0 +1,57 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { RaydiumCpSwap } from "../target/types/raydium_cp_swap";
+import { setupDepositTest, sync } from "./utils";
+import { assert } from "chai";
+import { getAssociatedTokenAddressSync, transfer } from "@solana/spl-token";
+
+describe("sync test", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const owner = anchor.Wallet.local().payer;
+
+  const program = anchor.workspace.RaydiumCpSwap as Program<RaydiumCpSwap>;
+
+  const confirmOptions = {
+    skipPreflight: true,
+  };
+
+  it("sync, tokens sent to the vaults become reserves", async () => {
+    const { poolAddress, poolState } = await setupDepositTest(
+      program,
+      anchor.getProvider().connection,
+      owner,
+      {
+        config_index: 0,
+        tradeFeeRate: new BN(10),
+        protocolFeeRate: new BN(1000),
+        fundFeeRate: new BN(25000),
+        create_fee: new BN(0),
+      },
+      { transferFeeBasisPoints: 0, MaxFee: 0 }
+    );
+
+    await transfer(
+      anchor.getProvider().connection,
+      owner,
+      getAssociatedTokenAddressSync(
+        poolState.token0Mint,
+        owner.publicKey,
+        false,
+        poolState.token0Program
+      ),
+      poolState.token0Vault,
+      owner,
+      1000,
+      [],
+      confirmOptions,
+      poolState.token0Program
+    );
+    let newPoolState = await program.account.poolState.fetch(poolAddress);
+    assert(newPoolState.reserve0.eq(poolState.reserve0));
+
+    await sync(program, owner, poolAddress, confirmOptions);
+    newPoolState = await program.account.poolState.fetch(poolAddress);
+    assert(newPoolState.reserve0.eq(poolState.reserve0.add(new BN(1000))));
+    assert(newPoolState.reserve1.eq(poolState.reserve1));
+  });
+});
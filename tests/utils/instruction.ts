@@ -14,6 +14,7 @@ import {
   TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
   getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
 } from "@solana/spl-token";
 import {
   accountExist,
@@ -25,6 +26,7 @@ import {
   getPoolVaultAddress,
   createTokenMintAndAssociatedTokenAccount,
   getOrcleAccountAddress,
+  getGlobalAdminAddress,
 } from "./index";
 
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
@@ -225,7 +227,8 @@ export async function createAmmConfig(
   protocolFeeRate: BN,
   fundFeeRate: BN,
   create_fee: BN,
-  confirmOptions?: ConfirmOptions
+  confirmOptions?: ConfirmOptions,
+  creatorFeeRate: BN = new BN(0)
 ): Promise<PublicKey> {
   const [address, _] = await getAmmConfigAddress(
     config_index,
@@ -234,6 +237,12 @@ export async function createAmmConfig(
   if (await accountExist(connection, address)) {
     return address;
   }
+  const globalAdmin = await createGlobalAdmin(
+    program,
+    connection,
+    owner,
+    confirmOptions
+  );
 
   const ix = await program.methods
     .createAmmConfig(
@@ -241,12 +250,14 @@ export async function createAmmConfig(
       tradeFeeRate,
       protocolFeeRate,
       fundFeeRate,
-      create_fee
+      create_fee,
+      creatorFeeRate
     )
     .accounts({
       owner: owner.publicKey,
       ammConfig: address,
       systemProgram: SystemProgram.programId,
+      globalAdmin,
     })
     .instruction();
 
@@ -255,6 +266,50 @@ export async function createAmmConfig(
   return address;
 }
 
+export async function createGlobalAdmin(
+  program: Program<RaydiumCpSwap>,
+  connection: Connection,
+  owner: Signer,
+  confirmOptions?: ConfirmOptions
+): Promise<PublicKey> {
+  const [address] = await getGlobalAdminAddress(program.programId);
+  if (await accountExist(connection, address)) {
+    return address;
+  }
+
+  const ix = await program.methods
+    .createGlobalAdmin()
+    .accounts({
+      owner: owner.publicKey,
+      globalAdmin: address,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+
+  const tx = await sendTransaction(connection, [ix], [owner], confirmOptions);
+  console.log("init global admin tx: ", tx);
+  return address;
+}
+
+export async function updateAmmConfig(
+  program: Program<RaydiumCpSwap>,
+  owner: Signer,
+  configAddress: PublicKey,
+  update: any,
+  confirmOptions?: ConfirmOptions
+) {
+  const [globalAdmin] = await getGlobalAdminAddress(program.programId);
+  const tx = await program.methods
+    .updateAmmConfig(update)
+    .accounts({
+      owner: owner.publicKey,
+      ammConfig: configAddress,
+      globalAdmin,
+    })
+    .rpc(confirmOptions);
+  return tx;
+}
+
 export async function initialize(
   program: Program<RaydiumCpSwap>,
   creator: Signer,
@@ -268,7 +323,8 @@ export async function initialize(
     initAmount0: new BN(10000000000),
     initAmount1: new BN(20000000000),
   },
-  createPoolFee = new PublicKey("DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8")
+  createPoolFee = new PublicKey("DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8"),
+  openTime = new BN(0)
 ) {
   const [auth] = await getAuthAddress(program.programId);
   const [poolAddress] = await getPoolAddress(
@@ -318,7 +374,7 @@ export async function initialize(
     token1Program
   );
   await program.methods
-    .initialize(initAmount.initAmount0, initAmount.initAmount1, new BN(0))
+    .initialize(initAmount.initAmount0, initAmount.initAmount1, openTime)
     .accounts({
       creator: creator.publicKey,
       ammConfig: configAddress,
@@ -402,6 +458,11 @@ export async function deposit(
     token1Program
   );
 
+  const [observationAddress] = await getOrcleAccountAddress(
+    poolAddress,
+    program.programId
+  );
+
   const tx = await program.methods
     .deposit(lp_token_amount, maximum_token_0_amount, maximum_token_1_amount)
     .accounts({
@@ -418,6 +479,8 @@ export async function deposit(
       vault0Mint: token0,
       vault1Mint: token1,
       lpMint: lpMintAddress,
+      ammConfig: configAddress,
+      observationState: observationAddress,
     })
     .rpc(confirmOptions);
   return tx;
@@ -480,6 +543,11 @@ export async function withdraw(
     token1Program
   );
 
+  const [observationAddress] = await getOrcleAccountAddress(
+    poolAddress,
+    program.programId
+  );
+
   const tx = await program.methods
     .withdraw(lp_token_amount, minimum_token_0_amount, minimum_token_1_amount)
     .accounts({
@@ -497,6 +565,8 @@ export async function withdraw(
       vault1Mint: token1,
       lpMint: lpMintAddress,
       memoProgram: new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"),
+      ammConfig: configAddress,
+      observationState: observationAddress,
     })
     .rpc(confirmOptions)
     .catch();
@@ -646,3 +716,173 @@ export async function swap_base_output(
 
   return tx;
 }
+
+export async function closePool(
+  program: Program<RaydiumCpSwap>,
+  authority: Signer,
+  poolAddress: PublicKey,
+  confirmOptions?: ConfirmOptions
+) {
+  const poolState = await program.account.poolState.fetch(poolAddress);
+  const ammConfig = await program.account.ammConfig.fetch(poolState.ammConfig);
+  const [vaultAuthority] = await getAuthAddress(program.programId);
+  const [globalAdmin] = await getGlobalAdminAddress(program.programId);
+  // the dust left in the vaults goes to the protocol owner
+  const protocolToken0 = await getOrCreateAssociatedTokenAccount(
+    program.provider.connection,
+    authority,
+    poolState.token0Mint,
+    ammConfig.protocolOwner,
+    true,
+    "processed",
+    confirmOptions,
+    poolState.token0Program
+  );
+  const protocolToken1 = await getOrCreateAssociatedTokenAccount(
+    program.provider.connection,
+    authority,
+    poolState.token1Mint,
+    ammConfig.protocolOwner,
+    true,
+    "processed",
+    confirmOptions,
+    poolState.token1Program
+  );
+
+  const tx = await program.methods
+    .closePool()
+    .accounts({
+      authority: authority.publicKey,
+      poolCreator: poolState.poolCreator,
+      vaultAuthority,
+      poolState: poolAddress,
+      ammConfig: poolState.ammConfig,
+      token0Vault: poolState.token0Vault,
+      token1Vault: poolState.token1Vault,
+      vault0Mint: poolState.token0Mint,
+      vault1Mint: poolState.token1Mint,
+      protocolToken0Account: protocolToken0.address,
+      protocolToken1Account: protocolToken1.address,
+      observationState: poolState.observationKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+      globalAdmin,
+    })
+    .rpc(confirmOptions);
+  return {
+    tx,
+    protocolToken0: protocolToken0.address,
+    protocolToken1: protocolToken1.address,
+  };
+}
+
+export async function cancelPool(
+  program: Program<RaydiumCpSwap>,
+  creator: Signer,
+  poolAddress: PublicKey,
+  confirmOptions?: ConfirmOptions
+) {
+  const poolState = await program.account.poolState.fetch(poolAddress);
+  const [authority] = await getAuthAddress(program.programId);
+
+  const tx = await program.methods
+    .cancelPool()
+    .accounts({
+      creator: creator.publicKey,
+      authority,
+      poolState: poolAddress,
+      token0Vault: poolState.token0Vault,
+      token1Vault: poolState.token1Vault,
+      vault0Mint: poolState.token0Mint,
+      vault1Mint: poolState.token1Mint,
+      lpMint: poolState.lpMint,
+      creatorLpToken: getAssociatedTokenAddressSync(
+        poolState.lpMint,
+        creator.publicKey,
+        false,
+        TOKEN_PROGRAM_ID
+      ),
+      creatorToken0: getAssociatedTokenAddressSync(
+        poolState.token0Mint,
+        creator.publicKey,
+        false,
+        poolState.token0Program
+      ),
+      creatorToken1: getAssociatedTokenAddressSync(
+        poolState.token1Mint,
+        creator.publicKey,
+        false,
+        poolState.token1Program
+      ),
+      observationState: poolState.observationKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+    })
+    .rpc(confirmOptions);
+  return tx;
+}
+
+export async function donate(
+  program: Program<RaydiumCpSwap>,
+  donor: Signer,
+  poolAddress: PublicKey,
+  token_0_amount: BN,
+  token_1_amount: BN,
+  proportional: boolean,
+  confirmOptions?: ConfirmOptions
+) {
+  const poolState = await program.account.poolState.fetch(poolAddress);
+  const donorToken0 = getAssociatedTokenAddressSync(
+    poolState.token0Mint,
+    donor.publicKey,
+    false,
+    poolState.token0Program
+  );
+  const donorToken1 = getAssociatedTokenAddressSync(
+    poolState.token1Mint,
+    donor.publicKey,
+    false,
+    poolState.token1Program
+  );
+
+  const tx = await program.methods
+    .donate(token_0_amount, token_1_amount, proportional)
+    .accounts({
+      donor: donor.publicKey,
+      poolState: poolAddress,
+      token0Account: donorToken0,
+      token1Account: donorToken1,
+      token0Vault: poolState.token0Vault,
+      token1Vault: poolState.token1Vault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenProgram2022: TOKEN_2022_PROGRAM_ID,
+      vault0Mint: poolState.token0Mint,
+      vault1Mint: poolState.token1Mint,
+      ammConfig: poolState.ammConfig,
+      observationState: poolState.observationKey,
+    })
+    .rpc(confirmOptions);
+  return tx;
+}
+
+export async function sync(
+  program: Program<RaydiumCpSwap>,
+  payer: Signer,
+  poolAddress: PublicKey,
+  confirmOptions?: ConfirmOptions
+) {
+  const poolState = await program.account.poolState.fetch(poolAddress);
+
+  const tx = await program.methods
+    .sync()
+    .accounts({
+      payer: payer.publicKey,
+      poolState: poolAddress,
+      token0Vault: poolState.token0Vault,
+      token1Vault: poolState.token1Vault,
+      ammConfig: poolState.ammConfig,
+      observationState: poolState.observationKey,
+    })
+    .rpc(confirmOptions);
+  return tx;
+}
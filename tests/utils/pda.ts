@@ -25,6 +25,10 @@ export const ORACLE_SEED = Buffer.from(
   anchor.utils.bytes.utf8.encode("observation")
 );
 
+export const GLOBAL_ADMIN_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("global_admin")
+);
+
 export function u16ToBytes(num: number) {
   const arr = new ArrayBuffer(2);
   const view = new DataView(arr);
@@ -125,3 +129,13 @@ export async function getOrcleAccountAddress(
   );
   return [address, bump];
 }
+
+export async function getGlobalAdminAddress(
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [GLOBAL_ADMIN_SEED],
+    programId
+  );
+  return [address, bump];
+}